@@ -14,12 +14,12 @@ pub fn input_generator(input: &str) -> Vec<i32> {
 }
 
 #[aoc(day1, part1)]
-pub fn part1(input: &Vec<i32>) -> i32 {
+pub fn part1(input: &[i32]) -> i32 {
     input.iter().max().unwrap().to_owned()
 }
 
 #[aoc(day1, part2)]
-pub fn part2(input: &Vec<i32>) -> i32 {
+pub fn part2(input: &[i32]) -> i32 {
     let mut mutable_input = input.to_owned();
     mutable_input.sort();
     mutable_input.iter().rev().take(3).sum()
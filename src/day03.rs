@@ -1,4 +1,7 @@
-use std::collections::HashSet;
+use std::{
+    collections::{BTreeSet, HashSet},
+    fmt::{self, Display},
+};
 
 pub struct Rucksack {
     all_items: HashSet<char>,
@@ -63,6 +66,172 @@ pub fn part2(input: &[Rucksack]) -> i32 {
         .sum()
 }
 
+#[derive(Debug, PartialEq, Eq)]
+pub enum ValidationError {
+    OddLength {
+        line: usize,
+        length: usize,
+    },
+    IllegalCharacter {
+        line: usize,
+        column: usize,
+        item: char,
+    },
+    NoSharedItem {
+        line: usize,
+    },
+    MultipleSharedItems {
+        line: usize,
+        items: Vec<char>,
+    },
+    IncompleteGroup {
+        first_line: usize,
+        size: usize,
+    },
+    NoBadge {
+        first_line: usize,
+    },
+    AmbiguousBadge {
+        first_line: usize,
+        badges: Vec<char>,
+    },
+}
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationError::OddLength { line, length } => {
+                write!(f, "line {}: odd number of items ({})", line, length)
+            }
+            ValidationError::IllegalCharacter { line, column, item } => {
+                write!(
+                    f,
+                    "line {}, column {}: illegal item {:?}",
+                    line, column, item
+                )
+            }
+            ValidationError::NoSharedItem { line } => {
+                write!(f, "line {}: compartments share no item", line)
+            }
+            ValidationError::MultipleSharedItems { line, items } => write!(
+                f,
+                "line {}: compartments share several items ({})",
+                line,
+                items.iter().collect::<String>()
+            ),
+            ValidationError::IncompleteGroup { first_line, size } => write!(
+                f,
+                "lines {}-{}: incomplete group of {} rucksacks",
+                first_line,
+                first_line + size - 1,
+                size
+            ),
+            ValidationError::NoBadge { first_line } => {
+                write!(
+                    f,
+                    "lines {}-{}: group has no badge",
+                    first_line,
+                    first_line + 2
+                )
+            }
+            ValidationError::AmbiguousBadge { first_line, badges } => write!(
+                f,
+                "lines {}-{}: group has several badge candidates ({})",
+                first_line,
+                first_line + 2,
+                badges.iter().collect::<String>()
+            ),
+        }
+    }
+}
+
+fn is_item(item: char) -> bool {
+    item.is_ascii_alphabetic()
+}
+
+pub fn validate(input: &str) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+    let lines: Vec<&str> = input.lines().collect();
+
+    for (i, line) in lines.iter().enumerate() {
+        let line_number = i + 1;
+        let length = line.chars().count();
+
+        for (column, item) in line.chars().enumerate() {
+            if !is_item(item) {
+                errors.push(ValidationError::IllegalCharacter {
+                    line: line_number,
+                    column: column + 1,
+                    item,
+                });
+            }
+        }
+
+        if length % 2 != 0 {
+            errors.push(ValidationError::OddLength {
+                line: line_number,
+                length,
+            });
+            continue;
+        }
+
+        let items: Vec<char> = line.chars().collect();
+        let (first, second) = items.split_at(length / 2);
+        let first: BTreeSet<char> = first
+            .iter()
+            .copied()
+            .filter(|item| is_item(*item))
+            .collect();
+        let second: BTreeSet<char> = second
+            .iter()
+            .copied()
+            .filter(|item| is_item(*item))
+            .collect();
+        let shared: Vec<char> = first.intersection(&second).copied().collect();
+
+        match shared.len() {
+            0 => errors.push(ValidationError::NoSharedItem { line: line_number }),
+            1 => (),
+            _ => errors.push(ValidationError::MultipleSharedItems {
+                line: line_number,
+                items: shared,
+            }),
+        }
+    }
+
+    for (i, group) in lines.chunks(3).enumerate() {
+        let first_line = i * 3 + 1;
+
+        if group.len() < 3 {
+            errors.push(ValidationError::IncompleteGroup {
+                first_line,
+                size: group.len(),
+            });
+            continue;
+        }
+
+        let badges: Vec<char> = group
+            .iter()
+            .map(|line| {
+                line.chars()
+                    .filter(|item| is_item(*item))
+                    .collect::<BTreeSet<char>>()
+            })
+            .reduce(|acc, items| acc.intersection(&items).copied().collect())
+            .unwrap()
+            .into_iter()
+            .collect();
+
+        match badges.len() {
+            0 => errors.push(ValidationError::NoBadge { first_line }),
+            1 => (),
+            _ => errors.push(ValidationError::AmbiguousBadge { first_line, badges }),
+        }
+    }
+
+    errors
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -81,4 +250,64 @@ CrZsJsPPZsGzwwsLwLmpwMDw"#,
         assert_eq!(part1(test_input), 157);
         assert_eq!(part2(test_input), 70);
     }
+
+    #[test]
+    fn validate_sample() {
+        assert_eq!(
+            validate(
+                r#"vJrwpWtwJgWrhcsFMMfFFhFp
+jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
+PmmdzqPrVvPwwTWBwg"#
+            ),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn validate_invalid_input() {
+        assert_eq!(
+            validate("abcab\nBcde\nab1b\nabab\nxyzx"),
+            vec![
+                ValidationError::OddLength { line: 1, length: 5 },
+                ValidationError::NoSharedItem { line: 2 },
+                ValidationError::IllegalCharacter {
+                    line: 3,
+                    column: 3,
+                    item: '1'
+                },
+                ValidationError::MultipleSharedItems {
+                    line: 4,
+                    items: vec!['a', 'b']
+                },
+                ValidationError::NoBadge { first_line: 1 },
+                ValidationError::IncompleteGroup {
+                    first_line: 4,
+                    size: 2
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn validate_multibyte_items() {
+        assert_eq!(
+            validate("éa\nab\nbé"),
+            vec![
+                ValidationError::IllegalCharacter {
+                    line: 1,
+                    column: 1,
+                    item: 'é'
+                },
+                ValidationError::NoSharedItem { line: 1 },
+                ValidationError::NoSharedItem { line: 2 },
+                ValidationError::IllegalCharacter {
+                    line: 3,
+                    column: 2,
+                    item: 'é'
+                },
+                ValidationError::NoSharedItem { line: 3 },
+                ValidationError::NoBadge { first_line: 1 },
+            ]
+        );
+    }
 }
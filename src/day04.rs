@@ -1,45 +1,127 @@
 use std::ops::Range;
 
-fn parse_range(input: &str) -> Range<i32> {
+use crate::interval_set::IntervalSet;
+
+type Assignment = IntervalSet<i32>;
+
+fn parse_range(input: &str) -> Assignment {
     let mut parts = input.split('-').map(|s| s.parse::<i32>().unwrap());
-    parts.next().unwrap()..parts.next().unwrap() + 1
+    IntervalSet::from(parts.next().unwrap()..parts.next().unwrap() + 1)
 }
 
 #[aoc_generator(day4)]
-pub fn input_generator(input: &str) -> Vec<(Range<i32>, Range<i32>)> {
+pub fn input_generator(input: &str) -> Vec<Vec<Assignment>> {
     input
         .lines()
-        .map(|line| {
-            let mut ranges = line.split(',').map(parse_range);
-            (ranges.next().unwrap(), ranges.next().unwrap())
-        })
+        .map(|line| line.split(',').map(parse_range).collect())
         .collect()
 }
 
-fn is_sub_range(range: &Range<i32>, other: &Range<i32>) -> bool {
-    range.start <= other.start && other.end <= range.end
+fn pairs(group: &[Assignment]) -> impl Iterator<Item = (&Assignment, &Assignment)> {
+    group
+        .iter()
+        .enumerate()
+        .flat_map(move |(i, range1)| group[i + 1..].iter().map(move |range2| (range1, range2)))
 }
 
 #[aoc(day4, part1)]
-pub fn part1(input: &[(Range<i32>, Range<i32>)]) -> usize {
+pub fn part1(input: &[Vec<Assignment>]) -> usize {
     input
         .iter()
-        .filter(|(range1, range2)| is_sub_range(range1, range2) || is_sub_range(range2, range1))
+        .filter(|group| {
+            pairs(group)
+                .any(|(range1, range2)| range1.is_superset(range2) || range2.is_superset(range1))
+        })
         .count()
 }
 
-fn ranges_overlap(range1: &Range<i32>, range2: &Range<i32>) -> bool {
-    range1.contains(&range2.start) || range2.contains(&range1.start)
-}
-
 #[aoc(day4, part2)]
-pub fn part2(input: &[(Range<i32>, Range<i32>)]) -> usize {
+pub fn part2(input: &[Vec<Assignment>]) -> usize {
     input
         .iter()
-        .filter(|(range1, range2)| ranges_overlap(range1, range2))
+        .filter(|group| pairs(group).any(|(range1, range2)| range1.overlaps(range2)))
         .count()
 }
 
+#[derive(Debug, PartialEq, Eq)]
+pub struct GroupAnalysis {
+    pub redundant: Vec<usize>,
+    pub uncovered: Assignment,
+    pub depths: Vec<(Range<i32>, usize)>,
+}
+
+impl GroupAnalysis {
+    pub fn max_depth(&self) -> usize {
+        self.depths
+            .iter()
+            .map(|(_, depth)| *depth)
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+fn get_overlap_depths(group: &[Assignment]) -> Vec<(Range<i32>, usize)> {
+    let mut events: Vec<(i32, isize)> = group
+        .iter()
+        .flat_map(Assignment::ranges)
+        .flat_map(|range| [(range.start, 1), (range.end, -1)])
+        .collect();
+    events.sort();
+
+    let mut depths: Vec<(Range<i32>, usize)> = Vec::new();
+    let mut depth = 0;
+
+    for (i, (section, change)) in events.iter().enumerate() {
+        depth += change;
+
+        match events.get(i + 1) {
+            Some((next_section, _)) if next_section > section && depth > 0 => {
+                match depths.last_mut() {
+                    Some((range, last_depth))
+                        if range.end == *section && *last_depth == depth as usize =>
+                    {
+                        range.end = *next_section
+                    }
+                    _ => depths.push((*section..*next_section, depth as usize)),
+                }
+            }
+            _ => (),
+        }
+    }
+
+    depths
+}
+
+pub fn analyze_group(group: &[Assignment]) -> GroupAnalysis {
+    let redundant = (0..group.len())
+        .filter(|&i| {
+            let others: Assignment = group
+                .iter()
+                .enumerate()
+                .filter(|&(j, _)| j != i)
+                .flat_map(|(_, assignment)| assignment.ranges().cloned())
+                .collect();
+
+            others.is_superset(&group[i])
+        })
+        .collect();
+
+    let covered: Assignment = group
+        .iter()
+        .flat_map(|assignment| assignment.ranges().cloned())
+        .collect();
+    let uncovered = covered
+        .span()
+        .map(|span| IntervalSet::from(span).difference(&covered))
+        .unwrap_or_default();
+
+    GroupAnalysis {
+        redundant,
+        uncovered,
+        depths: get_overlap_depths(group),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -58,4 +140,27 @@ mod tests {
         assert_eq!(part1(test_input), 2);
         assert_eq!(part2(test_input), 4);
     }
+
+    #[test]
+    fn group_analysis() {
+        let test_input = &input_generator("2-4,3-6,6-8,12-13,4-5");
+        let analysis = analyze_group(&test_input[0]);
+
+        assert_eq!(analysis.redundant, vec![1, 4]);
+        assert_eq!(analysis.uncovered, IntervalSet::from(9..12));
+        assert_eq!(
+            analysis.depths,
+            vec![
+                (2..3, 1),
+                (3..4, 2),
+                (4..5, 3),
+                (5..7, 2),
+                (7..9, 1),
+                (12..14, 1)
+            ]
+        );
+        assert_eq!(analysis.max_depth(), 3);
+        assert_eq!(part1(test_input), 1);
+        assert_eq!(part2(test_input), 1);
+    }
 }
@@ -1,3 +1,5 @@
+use std::fmt::{self, Display};
+
 #[derive(Clone, Copy)]
 pub struct Instruction {
     times: usize,
@@ -5,28 +7,154 @@ pub struct Instruction {
     to: usize,
 }
 
-type Input = (Vec<Vec<char>>, Vec<Instruction>);
+impl Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "move {} from {} to {}",
+            self.times,
+            self.from + 1,
+            self.to + 1
+        )
+    }
+}
+
+pub type Crate = String;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Stacks(pub Vec<Vec<Crate>>);
+
+impl Stacks {
+    pub fn top(&self) -> String {
+        self.0
+            .iter()
+            .filter_map(|stack| stack.last())
+            .cloned()
+            .collect()
+    }
+
+    fn get_column_width(&self, i: usize) -> usize {
+        let label_width = self.0[i]
+            .iter()
+            .map(|value| value.chars().count() + 2)
+            .max()
+            .unwrap_or(0);
+
+        label_width.max((i + 1).to_string().len() + 2)
+    }
+}
+
+fn center(string: &str, width: usize) -> String {
+    let padding = width - string.chars().count();
+    format!(
+        "{}{}{}",
+        " ".repeat(padding / 2),
+        string,
+        " ".repeat(padding - padding / 2)
+    )
+}
+
+impl Display for Stacks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let height = self.0.iter().map(Vec::len).max().unwrap_or(0);
+        let widths: Vec<usize> = (0..self.0.len())
+            .map(|i| self.get_column_width(i))
+            .collect();
+
+        for level in (0..height).rev() {
+            let row: Vec<String> = self
+                .0
+                .iter()
+                .zip(&widths)
+                .map(|(stack, &width)| match stack.get(level) {
+                    Some(value) => center(&format!("[{}]", value), width),
+                    None => " ".repeat(width),
+                })
+                .collect();
+
+            writeln!(f, "{}", row.join(" "))?;
+        }
+
+        let footer: Vec<String> = widths
+            .iter()
+            .enumerate()
+            .map(|(i, &width)| center(&(i + 1).to_string(), width))
+            .collect();
+
+        write!(f, "{}", footer.join(" "))
+    }
+}
+
+fn get_footer_columns(footer: &str) -> Vec<(usize, usize)> {
+    let mut columns = Vec::new();
+    let mut start = None;
+
+    for (i, ch) in footer.chars().chain([' ']).enumerate() {
+        match (ch.is_whitespace(), start) {
+            (false, None) => start = Some(i),
+            (true, Some(from)) => {
+                columns.push((from, i));
+                start = None;
+            }
+            _ => (),
+        }
+    }
+
+    columns
+}
+
+fn get_column_index(columns: &[(usize, usize)], (from, to): (usize, usize)) -> usize {
+    columns
+        .iter()
+        .position(|&(start, end)| start < to && from < end)
+        .unwrap_or_else(|| {
+            (0..columns.len())
+                .min_by_key(|&i| (columns[i].0 + columns[i].1).abs_diff(from + to))
+                .unwrap()
+        })
+}
+
+fn parse_stacks(drawing: &[&str]) -> Stacks {
+    let (footer, rows) = drawing.split_last().unwrap();
+    let columns = get_footer_columns(footer);
+    let mut stacks = vec![vec![]; columns.len()];
+
+    for row in rows.iter().rev() {
+        let chars: Vec<char> = row.chars().collect();
+        let mut i = 0;
+
+        while i < chars.len() {
+            if chars[i] != '[' {
+                i += 1;
+                continue;
+            }
+
+            let end = chars[i..]
+                .iter()
+                .position(|ch| *ch == ']')
+                .map(|offset| i + offset)
+                .unwrap();
+            let label: Crate = chars[i + 1..end].iter().collect();
+
+            stacks[get_column_index(&columns, (i, end + 1))].push(label);
+            i = end + 1;
+        }
+    }
+
+    Stacks(stacks)
+}
+
+type Input = (Stacks, Vec<Instruction>);
 
 #[aoc_generator(day5)]
 fn input_generator(input: &str) -> Input {
     let mut lines = input.lines();
 
-    let crates: Vec<Vec<char>> = lines
+    let drawing: Vec<&str> = lines
         .by_ref()
         .take_while(|line| line.trim() != "")
-        .map(|line| line.chars().skip(1).step_by(4).collect())
         .collect();
 
-    let mut stacks = vec![vec![]; crates.last().unwrap().len()];
-
-    crates[0..crates.len() - 1].iter().rev().for_each(|stack| {
-        stack.iter().enumerate().for_each(|(i, value)| {
-            if *value != ' ' {
-                stacks[i].push(*value)
-            }
-        })
-    });
-
     let instructions = lines
         .map(|line| {
             let mut parts = line.split(' ');
@@ -38,46 +166,144 @@ fn input_generator(input: &str) -> Input {
         })
         .collect();
 
-    (stacks, instructions)
+    (parse_stacks(&drawing), instructions)
 }
 
-#[aoc(day5, part1)]
-pub fn part1(input: &Input) -> String {
-    let (mut stacks, instructions) = input.clone();
+pub trait Crane {
+    fn execute(&self, stacks: &mut [Vec<Crate>], instruction: &Instruction);
+}
 
-    for Instruction { times, from, to } in instructions {
+pub struct CrateMover9000;
+
+impl Crane for CrateMover9000 {
+    fn execute(&self, stacks: &mut [Vec<Crate>], &Instruction { times, from, to }: &Instruction) {
         for _ in 0..times {
             let value = stacks.get_mut(from).unwrap().pop().unwrap();
             stacks.get_mut(to).unwrap().push(value);
         }
     }
+}
 
-    stacks.iter().map(|stack| stack.last().unwrap()).collect()
+pub struct CrateMover9001;
+
+impl Crane for CrateMover9001 {
+    fn execute(&self, stacks: &mut [Vec<Crate>], &Instruction { times, from, to }: &Instruction) {
+        let from_stack = stacks.get_mut(from).unwrap();
+        let lifted = from_stack.split_off(from_stack.len() - times);
+        stacks.get_mut(to).unwrap().extend(lifted);
+    }
 }
 
-#[aoc(day5, part2)]
-pub fn part2(input: &Input) -> String {
-    let (mut stacks, instructions) = input.clone();
+pub struct CustomCrane {
+    pub capacity: usize,
+    pub reversed: bool,
+}
 
-    for Instruction { times, from, to } in instructions {
-        for i in 0..times {
+impl Crane for CustomCrane {
+    fn execute(&self, stacks: &mut [Vec<Crate>], &Instruction { times, from, to }: &Instruction) {
+        let capacity = if self.capacity == 0 {
+            times
+        } else {
+            self.capacity
+        };
+        let mut remaining = times;
+
+        while remaining > 0 {
+            let lift_size = remaining.min(capacity);
             let from_stack = stacks.get_mut(from).unwrap();
-            let value = from_stack.remove(from_stack.len() + i - times);
-            stacks.get_mut(to).unwrap().push(value);
+            let mut lifted = from_stack.split_off(from_stack.len() - lift_size);
+
+            if self.reversed {
+                lifted.reverse();
+            }
+
+            stacks.get_mut(to).unwrap().extend(lifted);
+            remaining -= lift_size;
+        }
+    }
+}
+
+pub fn simulate(input: &Input, crane: &impl Crane) -> String {
+    let (mut stacks, instructions) = input.clone();
+
+    for instruction in &instructions {
+        crane.execute(&mut stacks.0, instruction);
+    }
+
+    stacks.top()
+}
+
+pub struct Playback<'a, C: Crane> {
+    crane: C,
+    instructions: &'a [Instruction],
+    history: Vec<Stacks>,
+    position: usize,
+}
+
+impl<'a, C: Crane> Playback<'a, C> {
+    pub fn new(stacks: Stacks, instructions: &'a [Instruction], crane: C) -> Playback<'a, C> {
+        Playback {
+            crane,
+            instructions,
+            history: vec![stacks],
+            position: 0,
         }
     }
 
-    stacks.iter().map(|stack| stack.last().unwrap()).collect()
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn current(&self) -> &Stacks {
+        &self.history[self.position]
+    }
+
+    pub fn last_instruction(&self) -> Option<&Instruction> {
+        self.instructions.get(self.position.checked_sub(1)?)
+    }
+
+    pub fn undo(&mut self) -> Option<&Stacks> {
+        self.position = self.position.checked_sub(1)?;
+        Some(self.current())
+    }
+
+    pub fn redo(&mut self) -> Option<&Stacks> {
+        let instruction = self.instructions.get(self.position)?;
+
+        if self.position + 1 == self.history.len() {
+            let mut stacks = self.current().clone();
+            self.crane.execute(&mut stacks.0, instruction);
+            self.history.push(stacks);
+        }
+
+        self.position += 1;
+        Some(self.current())
+    }
+}
+
+impl<C: Crane> Iterator for Playback<'_, C> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        self.redo().map(Stacks::to_string)
+    }
+}
+
+#[aoc(day5, part1)]
+pub fn part1(input: &Input) -> String {
+    simulate(input, &CrateMover9000)
+}
+
+#[aoc(day5, part2)]
+pub fn part2(input: &Input) -> String {
+    simulate(input, &CrateMover9001)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[test]
-    fn sample1() {
-        let test_input = input_generator(
-            "    [D]    
+    const SAMPLE: &str = "    [D]    
 [N] [C]    
 [Z] [M] [P]
  1   2   3 
@@ -85,10 +311,126 @@ mod tests {
 move 1 from 2 to 1
 move 3 from 1 to 3
 move 2 from 2 to 1
-move 1 from 1 to 2",
-        );
+move 1 from 1 to 2";
+
+    #[test]
+    fn sample1() {
+        let test_input = input_generator(SAMPLE);
 
         assert_eq!(part1(&test_input), "CMZ");
         assert_eq!(part2(&test_input), "MCD");
+        assert_eq!(
+            simulate(
+                &test_input,
+                &CustomCrane {
+                    capacity: 1,
+                    reversed: false
+                }
+            ),
+            "CMZ"
+        );
+        assert_eq!(
+            simulate(
+                &test_input,
+                &CustomCrane {
+                    capacity: usize::MAX,
+                    reversed: false
+                }
+            ),
+            "MCD"
+        );
+        assert_eq!(
+            simulate(
+                &test_input,
+                &CustomCrane {
+                    capacity: 0,
+                    reversed: false
+                }
+            ),
+            "MCD"
+        );
+        assert_eq!(
+            simulate(
+                &test_input,
+                &CustomCrane {
+                    capacity: 2,
+                    reversed: false
+                }
+            ),
+            "MCZ"
+        );
+        assert_eq!(
+            simulate(
+                &test_input,
+                &CustomCrane {
+                    capacity: 2,
+                    reversed: true
+                }
+            ),
+            "CMZ"
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let (stacks, instructions) = input_generator(SAMPLE);
+        let instructions: Vec<String> = instructions.iter().map(Instruction::to_string).collect();
+
+        assert_eq!(format!("{}\n\n{}", stacks, instructions.join("\n")), SAMPLE);
+        assert_eq!(
+            input_generator(include_str!("../input/2022/day5.txt"))
+                .0
+                .to_string(),
+            include_str!("../input/2022/day5.txt")
+                .split("\n\n")
+                .next()
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn playback() {
+        let (stacks, instructions) = input_generator(SAMPLE);
+        let mut playback = Playback::new(stacks.clone(), &instructions, CrateMover9000);
+
+        assert_eq!(
+            playback.next().unwrap(),
+            "[D]        
+[N] [C]    
+[Z] [M] [P]
+ 1   2   3 "
+        );
+        assert_eq!(playback.undo(), Some(&stacks));
+        assert_eq!(playback.undo(), None);
+        assert_eq!(playback.by_ref().count(), 4);
+        assert_eq!(playback.current().top(), "CMZ");
+        assert_eq!(
+            playback.last_instruction().unwrap().to_string(),
+            "move 1 from 1 to 2"
+        );
+        assert_eq!(playback.undo().unwrap().top(), "MZ");
+        assert_eq!(playback.redo().unwrap().top(), "CMZ");
+        assert_eq!(playback.redo(), None);
+    }
+
+    #[test]
+    fn wide_diagrams() {
+        let test_input = input_generator(
+            "[A]                                      [K]
+[B]     [AB]                             [L]
+[C] [D] [CD] [E] [F] [G] [H] [I] [J]    [M]
+ 1   2    3   4   5   6   7   8   9   10  11
+
+move 2 from 1 to 11
+move 2 from 3 to 10",
+        );
+
+        assert_eq!(part1(&test_input), "CDEFGHIJCDB");
+        assert_eq!(part2(&test_input), "CDEFGHIJABA");
+        assert_eq!(input_generator(&test_input.0.to_string()).0, test_input.0);
+
+        let stacks = Stacks(vec![vec!["AB".to_string()], vec!["C".to_string()]]);
+        assert_eq!(stacks.to_string(), "[AB] [C]\n 1    2 ");
+        assert_eq!(input_generator(&stacks.to_string()).0, stacks);
     }
 }
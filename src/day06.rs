@@ -1,41 +1,157 @@
-use std::collections::VecDeque;
+use std::{
+    collections::{HashMap, VecDeque},
+    hash::Hash,
+    io::{self, BufReader, Read},
+    ops::Range,
+};
 
-fn has_duplicates(deque: &VecDeque<char>) -> bool {
-    for (i1, ch1) in deque.iter().enumerate() {
-        for (i2, ch2) in deque.iter().enumerate() {
-            if i1 != i2 && ch1 == ch2 {
-                return true;
+pub struct MarkerDetector<T> {
+    size: usize,
+    window: VecDeque<T>,
+    counts: HashMap<T, usize>,
+    duplicates: usize,
+    position: usize,
+}
+
+impl<T: Copy + Eq + Hash> MarkerDetector<T> {
+    pub fn new(size: usize) -> MarkerDetector<T> {
+        MarkerDetector {
+            size,
+            window: VecDeque::with_capacity(size + 1),
+            counts: HashMap::new(),
+            duplicates: 0,
+            position: 0,
+        }
+    }
+
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn push(&mut self, symbol: T) -> bool {
+        self.position += 1;
+        self.window.push_back(symbol);
+
+        let count = self.counts.entry(symbol).or_insert(0);
+        *count += 1;
+        if *count == 2 {
+            self.duplicates += 1;
+        }
+
+        if self.window.len() > self.size {
+            let removed = self.window.pop_front().unwrap();
+            let count = self.counts.get_mut(&removed).unwrap();
+            *count -= 1;
+            if *count == 1 {
+                self.duplicates -= 1;
             }
         }
+
+        self.window.len() == self.size && self.duplicates == 0
+    }
+}
+
+pub fn find_marker<R: Read>(reader: R, marker_size: usize) -> io::Result<Option<usize>> {
+    let mut detector = MarkerDetector::new(marker_size);
+
+    for byte in BufReader::new(reader).bytes() {
+        if detector.push(byte?) {
+            return Ok(Some(detector.position()));
+        }
     }
 
-    false
+    Ok(None)
 }
 
-fn get_message_start_index(input: &str, marker_size: usize) -> usize {
-    let mut deque: VecDeque<char> = input.chars().take(marker_size - 1).collect();
+pub fn markers<T: Copy + Eq + Hash>(
+    symbols: impl IntoIterator<Item = T>,
+    marker_size: usize,
+) -> impl Iterator<Item = usize> {
+    let mut detector = MarkerDetector::new(marker_size);
 
-    for (i, ch) in input.chars().enumerate().skip(marker_size - 1) {
-        deque.push_back(ch);
+    symbols
+        .into_iter()
+        .enumerate()
+        .filter(move |(_, symbol)| detector.push(*symbol))
+        .map(|(i, _)| i + 1)
+}
 
-        if !has_duplicates(&deque) {
-            return i + 1;
+pub fn get_message_start_index(input: &str, marker_size: usize) -> Option<usize> {
+    markers(input.bytes(), marker_size).next()
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Frame {
+    pub packet_marker: Range<usize>,
+    pub header: Range<usize>,
+    pub message_marker: Option<Range<usize>>,
+    pub message: Option<Range<usize>>,
+}
+
+pub struct Framer<'a> {
+    data: &'a [u8],
+    packet_marker_size: usize,
+    message_marker_size: usize,
+    position: usize,
+}
+
+impl Framer<'_> {
+    pub fn new(data: &[u8], packet_marker_size: usize, message_marker_size: usize) -> Framer<'_> {
+        Framer {
+            data,
+            packet_marker_size,
+            message_marker_size,
+            position: 0,
         }
+    }
 
-        deque.pop_front();
+    fn find_marker_end(&self, from: usize, marker_size: usize) -> Option<usize> {
+        markers(self.data[from..].iter().copied(), marker_size)
+            .next()
+            .map(|end| from + end)
     }
+}
+
+impl Iterator for Framer<'_> {
+    type Item = Frame;
+
+    fn next(&mut self) -> Option<Frame> {
+        let packet_end = self.find_marker_end(self.position, self.packet_marker_size)?;
+        let packet_marker = packet_end - self.packet_marker_size..packet_end;
+
+        let Some(message_end) = self.find_marker_end(packet_end, self.message_marker_size) else {
+            self.position = self.data.len();
 
-    unreachable!()
+            return Some(Frame {
+                packet_marker,
+                header: packet_end..self.data.len(),
+                message_marker: None,
+                message: None,
+            });
+        };
+
+        let next_packet_start = self
+            .find_marker_end(message_end, self.packet_marker_size)
+            .map_or(self.data.len(), |end| end - self.packet_marker_size);
+        self.position = next_packet_start;
+
+        Some(Frame {
+            packet_marker,
+            header: packet_end..message_end - self.message_marker_size,
+            message_marker: Some(message_end - self.message_marker_size..message_end),
+            message: Some(message_end..next_packet_start),
+        })
+    }
 }
 
 #[aoc(day6, part1)]
 fn part1(input: &str) -> usize {
-    get_message_start_index(input, 4)
+    get_message_start_index(input, 4).unwrap()
 }
 
 #[aoc(day6, part2)]
 fn part2(input: &str) -> usize {
-    get_message_start_index(input, 14)
+    get_message_start_index(input, 14).unwrap()
 }
 
 #[cfg(test)]
@@ -56,4 +172,50 @@ mod tests {
         assert_eq!(part2("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg"), 29);
         assert_eq!(part2("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw"), 26);
     }
+
+    #[test]
+    fn large_markers() {
+        let symbols: Vec<u16> = (0..5000).chain(0..5000).collect();
+        let mut detector = MarkerDetector::new(5000);
+        let position = symbols.iter().position(|symbol| detector.push(*symbol));
+
+        assert_eq!(position, Some(4999));
+        assert_eq!(detector.position(), 5000);
+
+        let bytes: Vec<u8> = (0..=255).cycle().take(100000).collect();
+        assert_eq!(find_marker(&bytes[..], 256).unwrap(), Some(256));
+        assert_eq!(find_marker(&bytes[..], 257).unwrap(), None);
+    }
+
+    #[test]
+    fn all_markers() {
+        assert_eq!(
+            markers("aabcddab".bytes(), 3).collect::<Vec<_>>(),
+            vec![4, 5, 8]
+        );
+        assert_eq!(get_message_start_index("aaaa", 2), None);
+    }
+
+    #[test]
+    fn framing() {
+        let frames: Vec<Frame> = Framer::new(b"aabcdaxyzwbbefgh", 4, 5).collect();
+
+        assert_eq!(
+            frames,
+            vec![
+                Frame {
+                    packet_marker: 1..5,
+                    header: 5..5,
+                    message_marker: Some(5..10),
+                    message: Some(10..11),
+                },
+                Frame {
+                    packet_marker: 11..15,
+                    header: 15..16,
+                    message_marker: None,
+                    message: None,
+                },
+            ]
+        );
+    }
 }
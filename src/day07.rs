@@ -1,142 +1,736 @@
 use core::panic;
-use std::collections::HashMap;
+use std::{
+    collections::{BTreeMap, HashMap, HashSet, VecDeque},
+    fmt::Write,
+    fs, io,
+    path::Path,
+};
+
+pub type NodeId = usize;
+
+#[derive(Debug)]
+enum NodeKind {
+    File,
+    Directory(BTreeMap<String, NodeId>),
+}
 
 #[derive(Debug)]
-enum Node {
-    File(i32),
-    Directory(HashMap<String, Node>),
+struct Node {
+    name: String,
+    parent: Option<NodeId>,
+    size: u64,
+    kind: NodeKind,
+}
+
+#[derive(Debug)]
+pub struct FileSystem {
+    nodes: Vec<Node>,
+}
+
+impl Default for FileSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ReportFilter {
+    pub min_size: u64,
+    pub max_depth: Option<usize>,
 }
 
-impl Node {
-    fn new() -> Node {
-        Node::Directory(HashMap::new())
+impl ReportFilter {
+    fn accepts(&self, file_system: &FileSystem, id: NodeId) -> bool {
+        file_system.size(id) >= self.min_size
+            && self
+                .max_depth
+                .is_none_or(|max_depth| file_system.depth(id) <= max_depth)
+    }
+}
+
+fn json_string(string: &str) -> String {
+    let mut output = String::from("\"");
+
+    for ch in string.chars() {
+        match ch {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\n' => output.push_str("\\n"),
+            ch if ch.is_control() => write!(output, "\\u{:04x}", ch as u32).unwrap(),
+            ch => output.push(ch),
+        }
     }
 
-    fn add_file(&mut self, file_name: &str, file_size: i32) {
-        match self {
-            Node::Directory(ref mut children) => {
-                children.insert(file_name.to_string(), Node::File(file_size));
+    output.push('"');
+    output
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct CleanupPlan {
+    pub deleted: Vec<(String, u64)>,
+    pub freed: u64,
+}
+
+const MAX_PARTIAL_SUMS: usize = 1 << 16;
+
+struct CleanupSearch {
+    sizes: Vec<u64>,
+    ends: Vec<usize>,
+    eligible: Vec<bool>,
+    target: u64,
+}
+
+impl CleanupSearch {
+    fn search(&self, bucket_size: u64) -> Option<Option<(u64, Vec<usize>)>> {
+        let mut sums = vec![(0, 0, 0)];
+        let mut buckets = HashSet::from([0]);
+        let mut counts = vec![0; self.sizes.len() + 1];
+        let mut best: Option<(u64, usize, usize)> = None;
+        counts[self.sizes.len()] = 1;
+
+        for i in (0..self.sizes.len()).rev() {
+            if self.eligible[i] {
+                for previous in 0..counts[self.ends[i]] {
+                    let freed = sums[previous].0 + self.sizes[i];
+
+                    if freed >= self.target {
+                        if best.is_none_or(|(best, _, _)| freed <= best) {
+                            best = Some((freed, i, previous));
+                        }
+                    } else if buckets.insert(freed / bucket_size) {
+                        sums.push((freed, i, previous));
+                    }
+                }
+
+                if sums.len() > MAX_PARTIAL_SUMS {
+                    return None;
+                }
             }
-            _ => panic!(),
+
+            counts[i] = sums.len();
+
+            if best.is_some_and(|(freed, _, _)| freed == self.target) {
+                break;
+            }
+        }
+
+        Some(best.map(|(freed, mut i, mut previous)| {
+            let mut chosen = vec![i];
+
+            while previous != 0 {
+                (_, i, previous) = sums[previous];
+                chosen.push(i);
+            }
+
+            (freed, chosen)
+        }))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TraversalOrder {
+    DepthFirst,
+    BreadthFirst,
+}
+
+impl FileSystem {
+    pub fn new() -> FileSystem {
+        FileSystem {
+            nodes: vec![Node {
+                name: String::new(),
+                parent: None,
+                size: 0,
+                kind: NodeKind::Directory(BTreeMap::new()),
+            }],
         }
     }
 
-    fn add_directory(&mut self, directory_name: &str) {
-        match self {
-            Node::Directory(ref mut children) => {
-                children.insert(directory_name.to_string(), Node::Directory(HashMap::new()));
+    pub fn root(&self) -> NodeId {
+        0
+    }
+
+    pub fn name(&self, id: NodeId) -> &str {
+        &self.nodes[id].name
+    }
+
+    pub fn size(&self, id: NodeId) -> u64 {
+        self.nodes[id].size
+    }
+
+    pub fn parent(&self, id: NodeId) -> Option<NodeId> {
+        self.nodes[id].parent
+    }
+
+    pub fn is_directory(&self, id: NodeId) -> bool {
+        matches!(self.nodes[id].kind, NodeKind::Directory(_))
+    }
+
+    pub fn child(&self, id: NodeId, name: &str) -> Option<NodeId> {
+        match &self.nodes[id].kind {
+            NodeKind::Directory(children) => children.get(name).copied(),
+            NodeKind::File => None,
+        }
+    }
+
+    pub fn children(&self, id: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        let children = match &self.nodes[id].kind {
+            NodeKind::Directory(children) => Some(children.values().copied()),
+            NodeKind::File => None,
+        };
+
+        children.into_iter().flatten()
+    }
+
+    pub fn directories(&self) -> impl Iterator<Item = NodeId> + '_ {
+        (0..self.nodes.len()).filter(|id| self.is_directory(*id))
+    }
+
+    pub fn path(&self, id: NodeId) -> String {
+        let mut names = Vec::new();
+        let mut current = id;
+
+        while let Some(parent) = self.parent(current) {
+            names.push(self.name(current));
+            current = parent;
+        }
+
+        names.reverse();
+        format!("/{}", names.join("/"))
+    }
+
+    pub fn resolve_from(&self, from: NodeId, path: &str) -> Option<NodeId> {
+        let mut current = if path.starts_with('/') {
+            self.root()
+        } else {
+            from
+        };
+
+        for segment in path.split('/') {
+            current = match segment {
+                "" | "." => current,
+                ".." => self.parent(current).unwrap_or(current),
+                name => self.child(current, name)?,
+            };
+        }
+
+        Some(current)
+    }
+
+    pub fn resolve(&self, path: &str) -> Option<NodeId> {
+        self.resolve_from(self.root(), path)
+    }
+
+    pub fn create_directories(&mut self, from: NodeId, path: &str) -> Option<NodeId> {
+        let mut current = if path.starts_with('/') {
+            self.root()
+        } else {
+            from
+        };
+
+        for segment in path.split('/') {
+            current = match segment {
+                "" | "." => current,
+                ".." => self.parent(current).unwrap_or(current),
+                name => match self.child(current, name) {
+                    Some(id) if !self.is_directory(id) => return None,
+                    _ => self.add_directory(current, name),
+                },
+            };
+        }
+
+        Some(current)
+    }
+
+    fn add_node(&mut self, parent: NodeId, name: &str, kind: NodeKind) -> NodeId {
+        let id = self.nodes.len();
+
+        match &mut self.nodes[parent].kind {
+            NodeKind::Directory(children) => {
+                children.insert(name.to_string(), id);
             }
-            _ => panic!(),
+            NodeKind::File => panic!(),
+        }
+
+        self.nodes.push(Node {
+            name: name.to_string(),
+            parent: Some(parent),
+            size: 0,
+            kind,
+        });
+
+        id
+    }
+
+    pub fn add_directory(&mut self, parent: NodeId, name: &str) -> NodeId {
+        match self.child(parent, name) {
+            Some(id) if self.is_directory(id) => id,
+            Some(_) => panic!(),
+            None => self.add_node(parent, name, NodeKind::Directory(BTreeMap::new())),
+        }
+    }
+
+    pub fn add_file(&mut self, parent: NodeId, name: &str, size: u64) -> NodeId {
+        let id = match self.child(parent, name) {
+            Some(id) if !self.is_directory(id) => id,
+            Some(_) => panic!(),
+            None => self.add_node(parent, name, NodeKind::File),
+        };
+
+        let old_size = self.nodes[id].size;
+        let mut current = Some(id);
+
+        while let Some(node) = current {
+            self.nodes[node].size = self.nodes[node].size - old_size + size;
+            current = self.parent(node);
+        }
+
+        id
+    }
+
+    pub fn depth(&self, id: NodeId) -> usize {
+        let mut depth = 0;
+        let mut current = id;
+
+        while let Some(parent) = self.parent(current) {
+            depth += 1;
+            current = parent;
         }
+
+        depth
     }
 
-    fn get_size(&self) -> i32 {
-        match self {
-            Node::File(size) => *size,
-            Node::Directory(children) => children.values().map(|node| (*node).get_size()).sum(),
+    fn write_tree(&self, output: &mut String, id: NodeId, prefix: &str, filter: &ReportFilter) {
+        let children: Vec<NodeId> = self
+            .children(id)
+            .filter(|child| filter.accepts(self, *child))
+            .collect();
+
+        for (i, &child) in children.iter().enumerate() {
+            let is_last = i + 1 == children.len();
+            let (branch, indent) = if is_last {
+                ("└── ", "    ")
+            } else {
+                ("├── ", "│   ")
+            };
+
+            writeln!(
+                output,
+                "{}{}[{:>11}]  {}",
+                prefix,
+                branch,
+                self.size(child),
+                self.name(child)
+            )
+            .unwrap();
+
+            self.write_tree(output, child, &format!("{}{}", prefix, indent), filter);
         }
     }
 
-    fn get_total_size_of_small_folders(&self) -> i32 {
-        let mut sum = 0;
+    pub fn tree(&self, filter: &ReportFilter) -> String {
+        let mut output = format!("[{:>11}]  /\n", self.size(self.root()));
+        self.write_tree(&mut output, self.root(), "", filter);
+        output
+    }
+
+    pub fn du(&self, filter: &ReportFilter) -> Vec<(String, u64)> {
+        let mut directories: Vec<(String, u64)> = self
+            .directories()
+            .filter(|id| filter.accepts(self, *id))
+            .map(|id| (self.path(id), self.size(id)))
+            .collect();
+
+        directories.sort_by(|(path1, size1), (path2, size2)| {
+            size2.cmp(size1).then_with(|| path1.cmp(path2))
+        });
+        directories
+    }
+
+    pub fn du_report(&self, filter: &ReportFilter) -> String {
+        self.du(filter)
+            .iter()
+            .map(|(path, size)| format!("{}\t{}\n", size, path))
+            .collect()
+    }
+
+    fn write_json(&self, output: &mut String, id: NodeId) {
+        let name = if id == self.root() {
+            "/"
+        } else {
+            self.name(id)
+        };
+
+        if !self.is_directory(id) {
+            write!(
+                output,
+                "{{\"name\":{},\"type\":\"file\",\"size\":{}}}",
+                json_string(name),
+                self.size(id)
+            )
+            .unwrap();
+            return;
+        }
+
+        write!(
+            output,
+            "{{\"name\":{},\"type\":\"directory\",\"size\":{},\"children\":[",
+            json_string(name),
+            self.size(id)
+        )
+        .unwrap();
 
-        if let Node::Directory(children) = self {
-            let size = self.get_size();
-            if size <= 100000 {
-                sum += size;
+        for (i, child) in self.children(id).enumerate() {
+            if i > 0 {
+                output.push(',');
             }
 
-            sum += children
-                .values()
-                .map(Node::get_total_size_of_small_folders)
-                .sum::<i32>()
+            self.write_json(output, child);
         }
 
-        sum
+        output.push_str("]}");
+    }
+
+    pub fn to_json(&self) -> String {
+        let mut output = String::new();
+        self.write_json(&mut output, self.root());
+        output
+    }
+
+    fn collect_preorder(&self, id: NodeId, order: &mut Vec<(NodeId, usize)>) {
+        let start = order.len();
+        order.push((id, 0));
+
+        for child in self.children(id) {
+            self.collect_preorder(child, order);
+        }
+
+        order[start].1 = order.len();
+    }
+
+    pub fn plan_cleanup(
+        &self,
+        disk_size: u64,
+        required_size: u64,
+        include_files: bool,
+    ) -> Option<CleanupPlan> {
+        let used_size = self.size(self.root());
+        let target = required_size.saturating_sub(disk_size.checked_sub(used_size)?);
+
+        if target > used_size {
+            return None;
+        }
+
+        if target == 0 {
+            return Some(CleanupPlan {
+                deleted: vec![],
+                freed: 0,
+            });
+        }
+
+        let mut order = Vec::with_capacity(self.nodes.len());
+        self.collect_preorder(self.root(), &mut order);
+
+        let search = CleanupSearch {
+            sizes: order.iter().map(|&(id, _)| self.size(id)).collect(),
+            ends: order.iter().map(|&(_, end)| end).collect(),
+            eligible: order
+                .iter()
+                .map(|&(id, _)| self.is_directory(id) || include_files)
+                .collect(),
+            target,
+        };
+
+        // With too many distinct partial sums, keep one per bucket so the search stays bounded.
+        let (freed, chosen) = search
+            .search(1)
+            .or_else(|| search.search(target.div_ceil(MAX_PARTIAL_SUMS as u64)))??;
+        let mut deleted: Vec<(String, u64)> = chosen
+            .into_iter()
+            .map(|i| (self.path(order[i].0), self.size(order[i].0)))
+            .collect();
+        deleted.sort();
+
+        Some(CleanupPlan { deleted, freed })
     }
 
-    fn get_smallest_folder_size_larger_than(&self, min_size: i32) -> i32 {
-        let mut smallest_folder_size = i32::MAX;
+    fn write_listing(&self, output: &mut String, id: NodeId) {
+        output.push_str("$ ls\n");
 
-        if let Node::Directory(children) = self {
-            let size = self.get_size();
-            if size >= min_size {
-                smallest_folder_size = size;
+        for child in self.children(id) {
+            if self.is_directory(child) {
+                writeln!(output, "dir {}", self.name(child)).unwrap();
+            } else {
+                writeln!(output, "{} {}", self.size(child), self.name(child)).unwrap();
             }
+        }
+    }
+
+    fn write_depth_first_log(&self, output: &mut String, id: NodeId) {
+        self.write_listing(output, id);
+
+        for child in self.children(id).filter(|child| self.is_directory(*child)) {
+            writeln!(output, "$ cd {}", self.name(child)).unwrap();
+            self.write_depth_first_log(output, child);
+            output.push_str("$ cd ..\n");
+        }
+    }
+
+    pub fn to_command_log(&self, order: TraversalOrder) -> String {
+        let mut output = String::from("$ cd /\n");
+
+        match order {
+            TraversalOrder::DepthFirst => self.write_depth_first_log(&mut output, self.root()),
+            TraversalOrder::BreadthFirst => {
+                let mut queue = VecDeque::from([self.root()]);
 
-            let min_child_size = children
-                .values()
-                .map(|node| node.get_smallest_folder_size_larger_than(min_size))
-                .min()
-                .unwrap_or(i32::MAX);
+                while let Some(id) = queue.pop_front() {
+                    if id != self.root() {
+                        writeln!(output, "$ cd {}", self.path(id)).unwrap();
+                    }
 
-            if min_child_size < smallest_folder_size {
-                smallest_folder_size = min_child_size;
+                    self.write_listing(&mut output, id);
+                    queue.extend(self.children(id).filter(|child| self.is_directory(*child)));
+                }
             }
         }
 
-        smallest_folder_size
+        output
     }
 }
 
-fn get_root_node_for_command_log(input: &str) -> Node {
-    let mut root = Node::new();
-    let mut current_path: Vec<*mut Node> = vec![&mut root];
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Entry {
+    Directory,
+    File(u64),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Inconsistency {
+    Changed {
+        path: String,
+        before: Entry,
+        after: Entry,
+    },
+    Missing {
+        path: String,
+        before: Entry,
+    },
+    Added {
+        path: String,
+        after: Entry,
+    },
+    NotADirectory {
+        path: String,
+    },
+    UnexpectedLine {
+        line: usize,
+        text: String,
+    },
+}
+
+type Listing = BTreeMap<String, Entry>;
+
+#[derive(Default)]
+struct Replay {
+    file_system: FileSystem,
+    listings: HashMap<NodeId, Listing>,
+    inconsistencies: Vec<Inconsistency>,
+}
+
+impl Replay {
+    fn entry_path(&self, directory: NodeId, name: &str) -> String {
+        match self.file_system.path(directory).as_str() {
+            "/" => format!("/{}", name),
+            path => format!("{}/{}", path, name),
+        }
+    }
 
-    for line in input.lines() {
-        let parts: Vec<&str> = line.split(' ').collect();
+    fn change_directory(&mut self, from: Option<NodeId>, path: &str) -> Option<NodeId> {
+        let from = match from {
+            _ if path.starts_with('/') => self.file_system.root(),
+            Some(from) => from,
+            None => return None,
+        };
+        let directory = self.file_system.create_directories(from, path);
 
-        match parts[..] {
-            ["$", "cd", directory_name] => {
-                if directory_name == "/" {
-                    current_path.truncate(1);
-                } else if directory_name == ".." {
-                    current_path.pop().unwrap();
+        if directory.is_none() {
+            self.inconsistencies.push(Inconsistency::NotADirectory {
+                path: if path.starts_with('/') {
+                    path.to_string()
                 } else {
-                    let current_node = *current_path.last().unwrap();
+                    self.entry_path(from, path)
+                },
+            });
+        }
 
-                    unsafe {
-                        let child_node = match &mut *current_node {
-                            Node::Directory(children) => children.get_mut(directory_name).unwrap(),
-                            _ => panic!(),
-                        };
+        directory
+    }
+
+    fn apply_listing(&mut self, directory: NodeId, listing: Listing) {
+        if let Some(previous) = self.listings.get(&directory) {
+            let mut inconsistencies = Vec::new();
 
-                        current_path.push(&mut *child_node);
+            for (name, &before) in previous {
+                match listing.get(name) {
+                    Some(&after) if after != before => {
+                        inconsistencies.push(Inconsistency::Changed {
+                            path: self.entry_path(directory, name),
+                            before,
+                            after,
+                        })
                     }
+                    Some(_) => (),
+                    None => inconsistencies.push(Inconsistency::Missing {
+                        path: self.entry_path(directory, name),
+                        before,
+                    }),
+                }
+            }
+
+            for (name, &after) in &listing {
+                if !previous.contains_key(name) {
+                    inconsistencies.push(Inconsistency::Added {
+                        path: self.entry_path(directory, name),
+                        after,
+                    });
+                }
+            }
+
+            self.inconsistencies.extend(inconsistencies);
+        }
+
+        for (name, &entry) in &listing {
+            let existing = self.file_system.child(directory, name);
+
+            match (entry, existing) {
+                (Entry::Directory, Some(id)) if !self.file_system.is_directory(id) => (),
+                (Entry::File(_), Some(id)) if self.file_system.is_directory(id) => (),
+                (Entry::Directory, _) => {
+                    self.file_system.add_directory(directory, name);
+                }
+                (Entry::File(size), _) => {
+                    self.file_system.add_file(directory, name, size);
                 }
             }
-            ["$", "ls", ..] => (),
-            ["dir", directory_name] => unsafe {
-                (**current_path.last().unwrap()).add_directory(directory_name);
-            },
-            [file_size, file_name] => unsafe {
-                (**current_path.last().unwrap()).add_file(file_name, file_size.parse().unwrap());
-            },
-            _ => panic!(),
+        }
+
+        self.listings.insert(directory, listing);
+    }
+}
+
+pub fn replay_command_log(input: &str) -> (FileSystem, Vec<Inconsistency>) {
+    let mut replay = Replay::default();
+    let mut current = Some(replay.file_system.root());
+    let mut listing: Option<(Option<NodeId>, Listing)> = None;
+
+    for (i, line) in input.lines().enumerate() {
+        let parts: Vec<&str> = if line.starts_with("$ ") {
+            line.splitn(3, ' ').collect()
+        } else {
+            line.splitn(2, ' ').collect()
+        };
+
+        if parts[0] == "$" {
+            if let Some((Some(directory), entries)) = listing.take() {
+                replay.apply_listing(directory, entries);
+            }
+        }
+
+        let entry = match (&parts[..], &listing) {
+            (["$", "cd", path], _) => {
+                current = replay.change_directory(current, path);
+                continue;
+            }
+            (["$", "ls"], _) => {
+                listing = Some((current, Listing::new()));
+                continue;
+            }
+            (["$", "ls", path], _) => {
+                listing = Some((replay.change_directory(current, path), Listing::new()));
+                continue;
+            }
+            (["dir", name], Some(_)) => Some((name, Entry::Directory)),
+            ([size, name], Some(_)) if parts[0] != "$" => {
+                size.parse().ok().map(|size| (name, Entry::File(size)))
+            }
+            _ => None,
+        };
+
+        match entry {
+            Some((name, entry)) => {
+                listing.as_mut().unwrap().1.insert(name.to_string(), entry);
+            }
+            None => replay.inconsistencies.push(Inconsistency::UnexpectedLine {
+                line: i + 1,
+                text: line.to_string(),
+            }),
         }
     }
 
-    root
+    if let Some((Some(directory), entries)) = listing.take() {
+        replay.apply_listing(directory, entries);
+    }
+
+    (replay.file_system, replay.inconsistencies)
+}
+
+pub fn parse_command_log(input: &str) -> FileSystem {
+    replay_command_log(input).0
+}
+
+fn walk_directory(file_system: &mut FileSystem, id: NodeId, path: &Path) -> io::Result<()> {
+    for entry in fs::read_dir(path)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        let name = entry.file_name().to_string_lossy().into_owned();
+
+        if file_type.is_dir() {
+            let child = file_system.add_directory(id, &name);
+            walk_directory(file_system, child, &entry.path())?;
+        } else if file_type.is_file() {
+            file_system.add_file(id, &name, entry.metadata()?.len());
+        }
+    }
+
+    Ok(())
+}
+
+pub fn read_directory(path: &Path) -> io::Result<FileSystem> {
+    let mut file_system = FileSystem::new();
+    let root = file_system.root();
+    walk_directory(&mut file_system, root, path)?;
+    Ok(file_system)
 }
 
 #[aoc(day7, part1)]
-fn part1(input: &str) -> i32 {
-    get_root_node_for_command_log(input).get_total_size_of_small_folders()
+fn part1(input: &str) -> u64 {
+    let file_system = parse_command_log(input);
+
+    file_system
+        .directories()
+        .map(|id| file_system.size(id))
+        .filter(|size| *size <= 100000)
+        .sum()
 }
 
 #[aoc(day7, part2)]
-fn part2(input: &str) -> i32 {
-    let root = get_root_node_for_command_log(input);
-    root.get_smallest_folder_size_larger_than(30000000 - (70000000 - root.get_size()))
+fn part2(input: &str) -> u64 {
+    let file_system = parse_command_log(input);
+    let min_size = (30000000 + file_system.size(file_system.root())).saturating_sub(70000000);
+
+    file_system
+        .directories()
+        .map(|id| file_system.size(id))
+        .filter(|size| *size >= min_size)
+        .min()
+        .unwrap()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[test]
-    fn sample1() {
-        let input_string = "$ cd /
+    const SAMPLE: &str = "$ cd /
 $ ls
 dir a
 14848514 b.txt
@@ -160,7 +754,349 @@ $ ls
 5626152 d.ext
 7214296 k";
 
-        assert_eq!(part1(input_string), 95437);
-        assert_eq!(part2(input_string), 24933642);
+    #[test]
+    fn sample1() {
+        assert_eq!(part1(SAMPLE), 95437);
+        assert_eq!(part2(SAMPLE), 24933642);
+        assert_eq!(part2("$ cd /\n$ ls\ndir a\n5 x\n$ cd a\n$ ls\n3 y"), 3);
+        assert_eq!(
+            part2("$ cd /\n$ ls\ndir a\n50000000 x\n$ cd a\n$ ls\n40000000 y"),
+            90000000
+        );
+    }
+
+    #[test]
+    fn paths() {
+        let file_system = parse_command_log(SAMPLE);
+        let e = file_system.resolve("/a/e").unwrap();
+
+        assert_eq!(file_system.size(e), 584);
+        assert_eq!(file_system.path(e), "/a/e");
+        assert_eq!(file_system.parent(e), file_system.resolve("/a"));
+        assert_eq!(
+            file_system.resolve_from(e, "../../d/k"),
+            file_system.resolve("/d/k")
+        );
+        assert_eq!(file_system.resolve("/a/x"), None);
+        assert_eq!(
+            file_system
+                .children(file_system.resolve("/a").unwrap())
+                .map(|id| file_system.name(id))
+                .collect::<Vec<_>>(),
+            vec!["e", "f", "g", "h.lst"]
+        );
+        assert_eq!(file_system.size(file_system.root()), 48381165);
+    }
+
+    #[test]
+    fn robust_replay() {
+        let (file_system, inconsistencies) = replay_command_log(
+            "$ cd /a/b
+$ ls
+10 x
+dir c
+$ cd ../../d/e
+$ ls /a
+dir b
+5 y
+$ cd /
+$ ls a/b
+10 x
+dir c
+$ ls a/b
+20 x
+7 z
+$ cd a/b/c
+$ ls
+$ ls ..
+20 x
+7 z",
+        );
+
+        assert_eq!(file_system.size(file_system.resolve("/a/b").unwrap()), 27);
+        assert_eq!(file_system.size(file_system.resolve("/a").unwrap()), 32);
+        assert_eq!(file_system.size(file_system.root()), 32);
+        assert!(file_system.is_directory(file_system.resolve("/d/e").unwrap()));
+        assert!(file_system.is_directory(file_system.resolve("/a/b/c").unwrap()));
+        assert_eq!(
+            inconsistencies,
+            vec![
+                Inconsistency::Missing {
+                    path: "/a/b/c".to_string(),
+                    before: Entry::Directory,
+                },
+                Inconsistency::Changed {
+                    path: "/a/b/x".to_string(),
+                    before: Entry::File(10),
+                    after: Entry::File(20),
+                },
+                Inconsistency::Added {
+                    path: "/a/b/z".to_string(),
+                    after: Entry::File(7),
+                },
+            ]
+        );
+
+        let (file_system, inconsistencies) = replay_command_log(
+            "dir early
+$ cd /
+$ ls
+5 a
+$ cd a
+$ ls
+dir b
+$ ls /a/c
+3 d
+$ cd /
+$ pwd
+/
+$ ls
+5 a
+x y
+dir b",
+        );
+
+        assert_eq!(file_system.size(file_system.root()), 5);
+        assert!(file_system.is_directory(file_system.resolve("/b").unwrap()));
+        assert_eq!(
+            inconsistencies,
+            vec![
+                Inconsistency::UnexpectedLine {
+                    line: 1,
+                    text: "dir early".to_string(),
+                },
+                Inconsistency::NotADirectory {
+                    path: "/a".to_string(),
+                },
+                Inconsistency::NotADirectory {
+                    path: "/a/c".to_string(),
+                },
+                Inconsistency::UnexpectedLine {
+                    line: 11,
+                    text: "$ pwd".to_string(),
+                },
+                Inconsistency::UnexpectedLine {
+                    line: 12,
+                    text: "/".to_string(),
+                },
+                Inconsistency::UnexpectedLine {
+                    line: 15,
+                    text: "x y".to_string(),
+                },
+                Inconsistency::Added {
+                    path: "/b".to_string(),
+                    after: Entry::Directory,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn reports() {
+        let file_system = parse_command_log(SAMPLE);
+
+        assert_eq!(
+            file_system.tree(&ReportFilter {
+                min_size: 1000000,
+                max_depth: None,
+            }),
+            "[   48381165]  /
+├── [   14848514]  b.txt
+├── [    8504156]  c.dat
+└── [   24933642]  d
+    ├── [    5626152]  d.ext
+    ├── [    8033020]  d.log
+    ├── [    4060174]  j
+    └── [    7214296]  k
+"
+        );
+        assert_eq!(
+            file_system.tree(&ReportFilter {
+                min_size: 0,
+                max_depth: Some(1),
+            }),
+            "[   48381165]  /
+├── [      94853]  a
+├── [   14848514]  b.txt
+├── [    8504156]  c.dat
+└── [   24933642]  d
+"
+        );
+        assert_eq!(
+            file_system.du_report(&ReportFilter::default()),
+            "48381165\t/\n24933642\t/d\n94853\t/a\n584\t/a/e\n"
+        );
+        assert_eq!(
+            file_system.du(&ReportFilter {
+                min_size: 1000,
+                max_depth: Some(1),
+            }),
+            vec![
+                ("/".to_string(), 48381165),
+                ("/d".to_string(), 24933642),
+                ("/a".to_string(), 94853)
+            ]
+        );
+        assert_eq!(
+            parse_command_log("$ cd /\n$ ls\ndir x\n1 \"q\"\n$ cd x\n$ ls\n2 y").to_json(),
+            r#"{"name":"/","type":"directory","size":3,"children":[{"name":"\"q\"","type":"file","size":1},{"name":"x","type":"directory","size":2,"children":[{"name":"y","type":"file","size":2}]}]}"#
+        );
+    }
+
+    #[test]
+    fn cleanup_plan() {
+        let file_system = parse_command_log(SAMPLE);
+
+        assert_eq!(
+            file_system.plan_cleanup(70000000, 30000000, false),
+            Some(CleanupPlan {
+                deleted: vec![("/d".to_string(), 24933642)],
+                freed: 24933642,
+            })
+        );
+        assert_eq!(
+            file_system.plan_cleanup(70000000, 30000000, true),
+            Some(CleanupPlan {
+                deleted: vec![("/c.dat".to_string(), 8504156)],
+                freed: 8504156,
+            })
+        );
+        assert_eq!(
+            file_system.plan_cleanup(48400000, 100000, false),
+            Some(CleanupPlan {
+                deleted: vec![("/a".to_string(), 94853)],
+                freed: 94853,
+            })
+        );
+        assert_eq!(
+            file_system.plan_cleanup(48400000, 100000, true),
+            Some(CleanupPlan {
+                deleted: vec![("/a/f".to_string(), 29116), ("/a/h.lst".to_string(), 62596),],
+                freed: 91712,
+            })
+        );
+        assert_eq!(
+            file_system.plan_cleanup(70000000, 10000000, true),
+            Some(CleanupPlan {
+                deleted: vec![],
+                freed: 0,
+            })
+        );
+        assert_eq!(file_system.plan_cleanup(70000000, 80000000, true), None);
+
+        let mut file_system = FileSystem::new();
+        let a = file_system
+            .create_directories(file_system.root(), "/a")
+            .unwrap();
+        let b = file_system
+            .create_directories(file_system.root(), "/b")
+            .unwrap();
+        file_system.add_file(a, "x", 60_000_000_000);
+        file_system.add_file(a, "y", 70_000_000_000);
+        file_system.add_file(b, "z", 90_000_000_000);
+        file_system.add_file(file_system.root(), "c", 45_000_000_000);
+
+        assert_eq!(
+            file_system.plan_cleanup(300_000_000_000, 200_000_000_000, true),
+            Some(CleanupPlan {
+                deleted: vec![
+                    ("/a".to_string(), 130_000_000_000),
+                    ("/c".to_string(), 45_000_000_000)
+                ],
+                freed: 175_000_000_000,
+            })
+        );
+
+        let mut file_system = FileSystem::new();
+        for i in 0..40 {
+            let directory = file_system
+                .create_directories(file_system.root(), &format!("/{}", i))
+                .unwrap();
+            file_system.add_file(directory, "x", 2);
+        }
+
+        let plan = file_system.plan_cleanup(1080, 1041, false).unwrap();
+        assert_eq!(plan.freed, 42);
+        assert_eq!(plan.deleted.len(), 21);
+
+        let mut file_system = FileSystem::new();
+        for i in 0..40 {
+            file_system.add_file(file_system.root(), &i.to_string(), 1_000_000_007 + i * i);
+        }
+
+        let plan = file_system
+            .plan_cleanup(41_000_000_000, 20_999_989_180, true)
+            .unwrap();
+        assert!(plan.freed >= 20_000_010_000);
+        assert!(plan.freed < 20_010_000_000);
+        assert_eq!(
+            plan.deleted.iter().map(|(_, size)| size).sum::<u64>(),
+            plan.freed
+        );
+    }
+
+    #[test]
+    fn command_log_generator() {
+        let root = std::env::temp_dir().join(format!("aoc-2022-day07-{}", std::process::id()));
+        let files = [
+            ("a.txt", 120),
+            ("b/c.dat", 4000),
+            ("b/d/e", 17),
+            ("b/d/f.log", 250),
+            ("g/h", 1),
+            ("g/i/j/k", 33),
+            ("my docs/a b.txt", 5),
+        ];
+
+        for (path, size) in files {
+            let path = root.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, vec![b'x'; size]).unwrap();
+        }
+        fs::create_dir_all(root.join("empty")).unwrap();
+
+        let walked = read_directory(&root);
+        fs::remove_dir_all(&root).unwrap();
+        let walked = walked.unwrap();
+
+        assert_eq!(walked.size(walked.root()), 4426);
+        assert!(walked.resolve("/my docs/a b.txt").is_some());
+
+        for order in [TraversalOrder::DepthFirst, TraversalOrder::BreadthFirst] {
+            let (parsed, inconsistencies) = replay_command_log(&walked.to_command_log(order));
+
+            assert!(inconsistencies.is_empty());
+            assert_eq!(
+                parsed.du(&ReportFilter::default()),
+                walked.du(&ReportFilter::default())
+            );
+            assert_eq!(parsed.to_json(), walked.to_json());
+        }
+
+        assert_eq!(
+            parse_command_log(SAMPLE).to_command_log(TraversalOrder::BreadthFirst),
+            "$ cd /
+$ ls
+dir a
+14848514 b.txt
+8504156 c.dat
+dir d
+$ cd /a
+$ ls
+dir e
+29116 f
+2557 g
+62596 h.lst
+$ cd /d
+$ ls
+5626152 d.ext
+8033020 d.log
+4060174 j
+7214296 k
+$ cd /a/e
+$ ls
+584 i
+"
+        );
     }
 }
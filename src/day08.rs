@@ -1,30 +1,68 @@
+use std::fmt::Write;
+
 #[derive(Clone)]
-struct Tree {
-    height: i8,
-    is_visible: bool,
+pub struct Tree {
+    height: i32,
 }
 
 #[derive(Clone)]
-struct Forest {
+pub struct Forest {
     height: usize,
     width: usize,
-    trees: Vec<Tree>,
+    trees: Vec<Option<Tree>>,
 }
 
-impl From<&str> for Forest {
-    fn from(string: &str) -> Self {
-        let width = string.lines().next().unwrap().chars().count();
-
-        let trees: Vec<Tree> = string
-            .chars()
-            .filter(|ch| ('0'..='9').contains(ch))
-            .map(|ch| Tree {
-                height: (ch as u8 - b'0') as i8,
-                is_visible: false,
+fn parse_height(ch: char) -> Option<i32> {
+    match ch {
+        '0'..='9' => Some(ch as i32 - '0' as i32),
+        'a'..='z' => Some(ch as i32 - 'a' as i32 + 10),
+        'A'..='Z' => Some(ch as i32 - 'A' as i32 + 36),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ForestFormat {
+    Characters,
+    Separated,
+}
+
+impl ForestFormat {
+    pub fn detect(string: &str) -> ForestFormat {
+        if string.contains(',') {
+            ForestFormat::Separated
+        } else {
+            ForestFormat::Characters
+        }
+    }
+}
+
+impl Forest {
+    pub fn parse(string: &str, format: ForestFormat) -> Forest {
+        let rows: Vec<Vec<Option<Tree>>> = string
+            .lines()
+            .map(|line| match format {
+                ForestFormat::Separated => line
+                    .split(',')
+                    .map(|cell| cell.trim().parse().ok().map(|height| Tree { height }))
+                    .collect(),
+                ForestFormat::Characters => line
+                    .chars()
+                    .map(|ch| parse_height(ch).map(|height| Tree { height }))
+                    .collect(),
             })
             .collect();
 
-        let height = trees.len() / width;
+        let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+        let height = rows.len();
+
+        let trees = rows
+            .into_iter()
+            .flat_map(|mut row| {
+                row.resize(width, None);
+                row
+            })
+            .collect();
 
         Forest {
             height,
@@ -34,9 +72,9 @@ impl From<&str> for Forest {
     }
 }
 
-impl Forest {
-    fn get_tree_at(&mut self, x: usize, y: usize) -> Option<&mut Tree> {
-        self.trees.get_mut(y * self.width + x)
+impl From<&str> for Forest {
+    fn from(string: &str) -> Self {
+        Forest::parse(string, ForestFormat::detect(string))
     }
 }
 
@@ -45,116 +83,439 @@ fn input_generator(input: &str) -> Forest {
     input.into()
 }
 
-fn mark_invisible_trees(
-    forest: &mut Forest,
-    start: (usize, usize),
-    step: (isize, isize),
-    steps: usize,
-) {
-    let mut highest_tree = i8::MIN;
-    let (mut x, mut y) = start;
-
-    for _ in 0..steps {
-        if let Some(tree) = forest.get_tree_at(x, y) {
-            if tree.height > highest_tree {
-                tree.is_visible = true;
-                highest_tree = tree.height;
-            }
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    West,
+    East,
+    North,
+    South,
+    NorthWest,
+    NorthEast,
+    SouthWest,
+    SouthEast,
+}
+
+const DIRECTIONS: [Direction; 8] = [
+    Direction::West,
+    Direction::East,
+    Direction::North,
+    Direction::South,
+    Direction::NorthWest,
+    Direction::NorthEast,
+    Direction::SouthWest,
+    Direction::SouthEast,
+];
+
+impl Direction {
+    fn get_step(&self) -> (isize, isize) {
+        match self {
+            Direction::West => (-1, 0),
+            Direction::East => (1, 0),
+            Direction::North => (0, -1),
+            Direction::South => (0, 1),
+            Direction::NorthWest => (-1, -1),
+            Direction::NorthEast => (1, -1),
+            Direction::SouthWest => (-1, 1),
+            Direction::SouthEast => (1, 1),
         }
+    }
+}
+
+pub struct ViewingRules {
+    pub directions: Vec<Direction>,
+    pub tolerance: i32,
+}
 
-        x = (x as isize + step.0) as usize;
-        y = (y as isize + step.1) as usize;
+impl Default for ViewingRules {
+    fn default() -> Self {
+        ViewingRules {
+            directions: DIRECTIONS[..4].to_vec(),
+            tolerance: 0,
+        }
     }
 }
 
-#[aoc(day8, part1)]
-fn part1(forest: &Forest) -> usize {
-    let mut forest = forest.clone();
-    let Forest { width, height, .. } = forest;
+const HEATMAP_CHARS: &[u8] = b" .:-=+*#%@";
 
-    for y in 0..height {
-        mark_invisible_trees(&mut forest, (0, y), (1, 0), width);
-        mark_invisible_trees(&mut forest, (width - 1, y), (-1, 0), width);
+pub struct ForestAnalysis {
+    width: usize,
+    height: usize,
+    trees: Vec<bool>,
+    visible_from: Vec<u8>,
+    scenic_scores: Vec<usize>,
+}
+
+impl ForestAnalysis {
+    pub fn is_visible(&self, x: usize, y: usize) -> bool {
+        self.visible_from[y * self.width + x] != 0
     }
 
-    for x in 0..width {
-        mark_invisible_trees(&mut forest, (x, 0), (0, 1), height);
-        mark_invisible_trees(&mut forest, (x, height - 1), (0, -1), height);
+    pub fn visible_from(&self, x: usize, y: usize) -> Vec<Direction> {
+        let mask = self.visible_from[y * self.width + x];
+
+        DIRECTIONS
+            .into_iter()
+            .filter(|direction| mask & 1 << *direction as u8 != 0)
+            .collect()
     }
 
-    forest.trees.iter().filter(|tree| tree.is_visible).count()
-}
+    pub fn scenic_score(&self, x: usize, y: usize) -> usize {
+        self.scenic_scores[y * self.width + x]
+    }
+
+    pub fn visible_count(&self) -> usize {
+        self.visible_from.iter().filter(|mask| **mask != 0).count()
+    }
+
+    pub fn max_scenic_score(&self) -> usize {
+        self.scenic_scores.iter().copied().max().unwrap_or(0)
+    }
+
+    pub fn top_scenic_trees(&self, n: usize) -> Vec<((usize, usize), usize)> {
+        let mut trees: Vec<((usize, usize), usize)> = self
+            .scenic_scores
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| self.trees[*i])
+            .map(|(i, score)| ((i % self.width, i / self.width), *score))
+            .collect();
+
+        trees.sort_by(|(position1, score1), (position2, score2)| {
+            score2
+                .cmp(score1)
+                .then_with(|| (position1.1, position1.0).cmp(&(position2.1, position2.0)))
+        });
+        trees.truncate(n);
+        trees
+    }
+
+    fn get_scaled_score(&self, i: usize, max: usize, max_score: usize) -> usize {
+        match max_score {
+            0 => 0,
+            max_score => self.scenic_scores[i] * max / max_score,
+        }
+    }
 
-fn get_viewing_distance(
-    forest: &mut Forest,
-    position: (usize, usize),
-    step: (isize, isize),
-    distance_to_border: usize,
-) -> i32 {
-    let mut viewing_distance = 1;
-    let (mut x, mut y) = position;
-    let tree_height = forest.get_tree_at(x, y).unwrap().height;
+    pub fn visibility_map(&self) -> String {
+        let mut output = String::new();
 
-    for _ in 0..distance_to_border - 1 {
-        x = (x as isize + step.0) as usize;
-        y = (y as isize + step.1) as usize;
+        for (i, mask) in self.visible_from.iter().enumerate() {
+            if i > 0 && i % self.width == 0 {
+                output.push('\n');
+            }
+
+            output.push(match mask.count_ones() {
+                _ if !self.trees[i] => ' ',
+                0 => '.',
+                count => char::from_digit(count, 10).unwrap(),
+            });
+        }
+
+        output
+    }
+
+    pub fn heatmap(&self) -> String {
+        let max_score = self.max_scenic_score();
+
+        (0..self.height)
+            .map(|y| {
+                (y * self.width..(y + 1) * self.width)
+                    .map(|i| {
+                        let level = self.get_scaled_score(i, HEATMAP_CHARS.len() - 1, max_score);
+                        HEATMAP_CHARS[level] as char
+                    })
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
 
-        let other_tree_height = forest.get_tree_at(x, y).unwrap().height;
+    pub fn to_pgm(&self) -> String {
+        let max_score = self.max_scenic_score();
+        let mut output = format!("P2\n{} {}\n255\n", self.width, self.height);
 
-        if other_tree_height >= tree_height {
-            return viewing_distance;
+        for y in 0..self.height {
+            let values: Vec<String> = (y * self.width..(y + 1) * self.width)
+                .map(|i| self.get_scaled_score(i, 255, max_score).to_string())
+                .collect();
+            writeln!(output, "{}", values.join(" ")).unwrap();
         }
 
-        viewing_distance += 1;
+        output
     }
 
-    viewing_distance
+    pub fn to_ppm(&self) -> String {
+        let max_score = self.max_scenic_score();
+        let mut output = format!("P3\n{} {}\n255\n", self.width, self.height);
+
+        for y in 0..self.height {
+            let values: Vec<String> = (y * self.width..(y + 1) * self.width)
+                .map(|i| {
+                    let intensity = 32 + self.get_scaled_score(i, 223, max_score);
+
+                    if self.visible_from[i] != 0 {
+                        format!("0 {} 0", intensity)
+                    } else {
+                        format!("{} 0 0", intensity)
+                    }
+                })
+                .collect();
+            writeln!(output, "{}", values.join("  ")).unwrap();
+        }
+
+        output
+    }
 }
 
-fn get_scenic_score(forest: &mut Forest, position: (usize, usize)) -> i32 {
-    let (x, y) = position;
-    let width = forest.width;
-    let height = forest.height;
+fn scan_line(
+    forest: &Forest,
+    line: &[usize],
+    direction: Direction,
+    tolerance: i32,
+    analysis: &mut ForestAnalysis,
+) {
+    let mut highest_tree = i32::MIN;
+    let mut blocking_trees: Vec<(usize, i32)> = Vec::with_capacity(line.len());
+
+    for (position, &i) in line.iter().enumerate() {
+        let Some(tree) = &forest.trees[i] else {
+            continue;
+        };
+        let min_blocking_height = tree.height - tolerance;
 
-    get_viewing_distance(forest, position, (1, 0), width - x - 1)
-        * get_viewing_distance(forest, position, (-1, 0), x)
-        * get_viewing_distance(forest, position, (0, 1), height - y - 1)
-        * get_viewing_distance(forest, position, (0, -1), y)
+        if highest_tree < min_blocking_height {
+            analysis.visible_from[i] |= 1 << direction as u8;
+        }
+        highest_tree = highest_tree.max(tree.height);
+
+        if tolerance == 0 {
+            while let Some((_, height)) = blocking_trees.last() {
+                if *height >= tree.height {
+                    break;
+                }
+
+                blocking_trees.pop();
+            }
+
+            analysis.scenic_scores[i] *= match blocking_trees.last() {
+                Some((blocking_position, _)) => position - blocking_position,
+                None => position,
+            };
+        } else {
+            let blocking_tree_count =
+                blocking_trees.partition_point(|(_, height)| *height >= min_blocking_height);
+            analysis.scenic_scores[i] *= match blocking_tree_count {
+                0 => position,
+                count => position - blocking_trees[count - 1].0,
+            };
+
+            while let Some((_, height)) = blocking_trees.last() {
+                if *height > tree.height {
+                    break;
+                }
+
+                blocking_trees.pop();
+            }
+        }
+        blocking_trees.push((position, tree.height));
+    }
 }
 
-#[aoc(day8, part2)]
-fn part2(forest: &Forest) -> i32 {
-    let mut forest = forest.clone();
-    let Forest { width, height, .. } = forest;
-    let mut max_scenic_score = i32::MIN;
-
-    for x in 1..width - 1 {
-        for y in 1..height - 1 {
-            let scenic_score = get_scenic_score(&mut forest, (x, y));
-            if scenic_score > max_scenic_score {
-                max_scenic_score = scenic_score;
+fn get_lines(forest: &Forest, direction: Direction) -> Vec<Vec<usize>> {
+    let Forest { width, height, .. } = *forest;
+    let (dx, dy) = direction.get_step();
+    let in_forest =
+        |x: isize, y: isize| 0 <= x && x < width as isize && 0 <= y && y < height as isize;
+
+    (0..height as isize)
+        .flat_map(|y| (0..width as isize).map(move |x| (x, y)))
+        .filter(|&(x, y)| !in_forest(x + dx, y + dy))
+        .map(|(mut x, mut y)| {
+            let mut line = Vec::new();
+
+            while in_forest(x, y) {
+                line.push(y as usize * width + x as usize);
+                x -= dx;
+                y -= dy;
             }
+
+            line
+        })
+        .collect()
+}
+
+pub fn analyze_forest_with(forest: &Forest, rules: &ViewingRules) -> ForestAnalysis {
+    let mut analysis = ForestAnalysis {
+        width: forest.width,
+        height: forest.height,
+        trees: forest.trees.iter().map(Option::is_some).collect(),
+        visible_from: vec![0; forest.trees.len()],
+        scenic_scores: forest
+            .trees
+            .iter()
+            .map(|tree| usize::from(tree.is_some()))
+            .collect(),
+    };
+
+    for &direction in &rules.directions {
+        for line in get_lines(forest, direction) {
+            scan_line(forest, &line, direction, rules.tolerance, &mut analysis);
         }
     }
 
-    max_scenic_score
+    analysis
+}
+
+pub fn analyze_forest(forest: &Forest) -> ForestAnalysis {
+    analyze_forest_with(forest, &ViewingRules::default())
+}
+
+#[aoc(day8, part1)]
+fn part1(forest: &Forest) -> usize {
+    analyze_forest(forest).visible_count()
+}
+
+#[aoc(day8, part2)]
+fn part2(forest: &Forest) -> usize {
+    analyze_forest(forest).max_scenic_score()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[test]
-    fn sample1() {
-        let test_input = input_generator(
-            "30373
+    const SAMPLE: &str = "30373
 25512
 65332
 33549
-35390",
-        );
+35390";
+
+    #[test]
+    fn sample1() {
+        let test_input = input_generator(SAMPLE);
 
         assert_eq!(part1(&test_input), 21);
         assert_eq!(part2(&test_input), 8);
     }
+
+    #[test]
+    fn wide_forest() {
+        let width = 50000;
+        let row: String = (0..width)
+            .map(|x| char::from(b'0' + (x % 10) as u8))
+            .collect();
+        let test_input = input_generator(&[row.as_str(), row.as_str(), row.as_str()].join("\n"));
+
+        assert_eq!(part1(&test_input), 2 * width + 11);
+        assert_eq!(part2(&test_input), 100);
+    }
+
+    #[test]
+    fn exports() {
+        let analysis = analyze_forest(&input_generator(SAMPLE));
+
+        assert_eq!(
+            analysis.visible_from(1, 1),
+            vec![Direction::West, Direction::North]
+        );
+        assert_eq!(
+            analysis.visible_from(2, 3),
+            vec![Direction::West, Direction::South]
+        );
+        assert!(analysis.visible_from(2, 2).is_empty());
+        assert_eq!(
+            analysis.top_scenic_trees(3),
+            vec![((2, 3), 8), ((1, 2), 6), ((2, 1), 4)]
+        );
+        assert_eq!(
+            analysis.visibility_map(),
+            "21132
+122.1
+41.11
+1.2.4
+22142"
+        );
+        assert_eq!(
+            analysis.heatmap(),
+            "     
+ .=. 
+ *.: 
+ .@- 
+     "
+        );
+        assert_eq!(
+            analysis.to_pgm().lines().take(5).collect::<Vec<_>>(),
+            vec!["P2", "5 5", "255", "0 0 0 0 0", "0 31 127 31 0"]
+        );
+        assert!(analysis
+            .to_ppm()
+            .starts_with("P3\n5 5\n255\n0 32 0  0 32 0"));
+
+        let empty = analyze_forest(&Forest {
+            height: 0,
+            width: 0,
+            trees: vec![],
+        });
+        assert_eq!(empty.heatmap(), "");
+        assert_eq!(empty.to_pgm(), "P2\n0 0\n255\n");
+        assert_eq!(empty.to_ppm(), "P3\n0 0\n255\n");
+    }
+
+    #[test]
+    fn viewing_rules() {
+        let forest = input_generator(SAMPLE);
+        let analysis = analyze_forest_with(
+            &forest,
+            &ViewingRules {
+                directions: DIRECTIONS.to_vec(),
+                tolerance: 0,
+            },
+        );
+
+        assert_eq!(analysis.visible_count(), 22);
+        assert_eq!(
+            analysis.visible_from(3, 3),
+            vec![
+                Direction::NorthEast,
+                Direction::SouthWest,
+                Direction::SouthEast
+            ]
+        );
+        assert_eq!(analysis.scenic_score(2, 3), 16);
+
+        let analysis = analyze_forest_with(
+            &forest,
+            &ViewingRules {
+                directions: DIRECTIONS[..4].to_vec(),
+                tolerance: 1,
+            },
+        );
+
+        assert_eq!(analysis.visible_count(), 20);
+        assert_eq!(analysis.visible_from(1, 2), vec![Direction::East]);
+        assert!(analysis.visible_from(3, 2).is_empty());
+        assert_eq!(analysis.scenic_score(2, 2), 1);
+    }
+
+    #[test]
+    fn irregular_forests() {
+        let letters = analyze_forest(&input_generator("az\nZ.\n0"));
+
+        assert!(letters.visible_from(1, 1).is_empty());
+        assert_eq!(letters.visible_count(), 4);
+        assert_eq!(letters.top_scenic_trees(1), vec![((0, 0), 0)]);
+
+        let numbers = analyze_forest(&input_generator("10,12,10\n11,,11\n10,12,10,99"));
+
+        assert_eq!(numbers.visible_count(), 9);
+        assert_eq!(numbers.scenic_score(2, 1), 2);
+        assert_eq!(numbers.scenic_score(1, 0), 0);
+        assert_eq!(numbers.visibility_map(), "232 \n3 3 \n2214");
+
+        let mixed = analyze_forest(&input_generator("1,10\n12\n10"));
+        assert_eq!(mixed.visibility_map(), "24\n4 \n3 ");
+
+        let column = analyze_forest(&Forest::parse("10\n12\n10", ForestFormat::Separated));
+        assert_eq!(column.visibility_map(), "3\n4\n3");
+    }
 }
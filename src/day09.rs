@@ -1,10 +1,34 @@
-use std::collections::HashSet;
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Write,
+    fs, io,
+    path::Path,
+};
 
 pub enum Direction {
     Up,
     Down,
     Left,
     Right,
+    UpLeft,
+    UpRight,
+    DownLeft,
+    DownRight,
+}
+
+impl Direction {
+    fn get_step(&self) -> (i32, i32) {
+        match self {
+            Direction::Up => (0, -1),
+            Direction::Down => (0, 1),
+            Direction::Left => (-1, 0),
+            Direction::Right => (1, 0),
+            Direction::UpLeft => (-1, -1),
+            Direction::UpRight => (1, -1),
+            Direction::DownLeft => (-1, 1),
+            Direction::DownRight => (1, 1),
+        }
+    }
 }
 
 pub struct Motion {
@@ -25,6 +49,10 @@ fn input_generator(input: &str) -> Vec<Motion> {
                     "D" => Direction::Down,
                     "L" => Direction::Left,
                     "R" => Direction::Right,
+                    "UL" => Direction::UpLeft,
+                    "UR" => Direction::UpRight,
+                    "DL" => Direction::DownLeft,
+                    "DR" => Direction::DownRight,
                     _ => panic!(),
                 },
                 steps: parts.next().unwrap().parse().unwrap(),
@@ -33,69 +61,492 @@ fn input_generator(input: &str) -> Vec<Motion> {
         .collect()
 }
 
+pub struct Motion3D {
+    step: [i32; 3],
+    steps: i32,
+}
+
+pub fn parse_motions_3d(input: &str) -> Vec<Motion3D> {
+    input
+        .lines()
+        .map(|line| {
+            let mut parts = line.split(' ');
+            let mut step = [0; 3];
+
+            for ch in parts.next().unwrap().chars() {
+                let (axis, delta) = match ch {
+                    'L' => (0, -1),
+                    'R' => (0, 1),
+                    'U' => (1, -1),
+                    'D' => (1, 1),
+                    'F' => (2, -1),
+                    'B' => (2, 1),
+                    _ => panic!(),
+                };
+
+                assert_eq!(step[axis], 0);
+                step[axis] = delta;
+            }
+
+            Motion3D {
+                step,
+                steps: parts.next().unwrap().parse().unwrap(),
+            }
+        })
+        .collect()
+}
+
+fn get_new_knot_pos_n<const N: usize>(knot: [i32; N], next_knot: [i32; N]) -> [i32; N] {
+    if (0..N).all(|i| next_knot[i].abs_diff(knot[i]) <= 1) {
+        return knot;
+    }
+
+    let mut new_knot = knot;
+
+    for i in 0..N {
+        new_knot[i] += (next_knot[i] - knot[i]).signum();
+    }
+
+    new_knot
+}
+
 fn get_new_knot_pos((x, y): (i32, i32), (nx, ny): (i32, i32)) -> (i32, i32) {
-    match (nx - x, ny - y) {
-        (2, 0) => (nx - 1, ny),
-        (-2, 0) => (nx + 1, ny),
-        (0, 2) => (nx, ny - 1),
-        (0, -2) => (nx, ny + 1),
-        (dx, dy) if dx.abs() <= 1 && dy.abs() <= 1 => (x, y),
-        (dx, dy) => (x + dx.clamp(-1, 1), y + dy.clamp(-1, 1)),
+    let [x, y] = get_new_knot_pos_n([x, y], [nx, ny]);
+    (x, y)
+}
+
+fn move_knots(knots: &mut [(i32, i32)], direction: &Direction) {
+    let (dx, dy) = direction.get_step();
+    let head = &mut knots[0];
+
+    head.0 += dx;
+    head.1 += dy;
+
+    for i in 1..knots.len() {
+        knots[i] = get_new_knot_pos(knots[i], knots[i - 1]);
     }
 }
 
-fn count_visited_tiles<const L: usize>(motions: &[Motion]) -> usize {
-    let mut knots = [(0, 0); L];
-    let mut visited_tiles = HashSet::<(i32, i32)>::new();
+pub fn count_visited_cells_3d(motions: &[Motion3D], length: usize) -> usize {
+    let mut knots = vec![[0; 3]; length];
+    let mut visited_cells = HashSet::<[i32; 3]>::new();
+
+    for Motion3D { step, steps } in motions {
+        for _ in 0..*steps {
+            for (axis, delta) in step.iter().enumerate() {
+                knots[0][axis] += delta;
+            }
+
+            for i in 1..length {
+                knots[i] = get_new_knot_pos_n(knots[i], knots[i - 1]);
+            }
+
+            visited_cells.insert(knots[length - 1]);
+        }
+    }
+
+    visited_cells.len()
+}
+
+const DENSE_BITMAP_LIMIT: u64 = 1 << 26;
+const CHUNK_SIZE: i32 = 64;
+
+trait VisitedSet {
+    fn insert(&mut self, position: (i32, i32));
+    fn len(&self) -> usize;
+}
+
+struct DenseBitmap {
+    bounds: BoundingBox,
+    bits: Vec<u64>,
+    count: usize,
+}
+
+impl DenseBitmap {
+    fn new(bounds: BoundingBox) -> DenseBitmap {
+        DenseBitmap {
+            bounds,
+            bits: vec![0; bounds.area().div_ceil(64) as usize],
+            count: 0,
+        }
+    }
+}
+
+impl VisitedSet for DenseBitmap {
+    fn insert(&mut self, position: (i32, i32)) {
+        let i = self.bounds.get_index(position).unwrap();
+        let mask = 1 << (i % 64);
+
+        if self.bits[i / 64] & mask == 0 {
+            self.bits[i / 64] |= mask;
+            self.count += 1;
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.count
+    }
+}
+
+#[derive(Default)]
+struct ChunkedBitmap {
+    chunks: HashMap<(i32, i32), Box<[u64; CHUNK_SIZE as usize]>>,
+    count: usize,
+}
+
+impl VisitedSet for ChunkedBitmap {
+    fn insert(&mut self, (x, y): (i32, i32)) {
+        let chunk = self
+            .chunks
+            .entry((x.div_euclid(CHUNK_SIZE), y.div_euclid(CHUNK_SIZE)))
+            .or_insert_with(|| Box::new([0; CHUNK_SIZE as usize]));
+        let row = &mut chunk[y.rem_euclid(CHUNK_SIZE) as usize];
+        let mask = 1 << x.rem_euclid(CHUNK_SIZE);
+
+        if *row & mask == 0 {
+            *row |= mask;
+            self.count += 1;
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.count
+    }
+}
+
+fn get_head_bounding_box(motions: &[Motion]) -> BoundingBox {
+    let mut head = (0, 0);
+    let mut bounds = BoundingBox {
+        min: head,
+        max: head,
+    };
 
     for Motion { direction, steps } in motions {
+        let (dx, dy) = direction.get_step();
+        head = (head.0 + dx * steps, head.1 + dy * steps);
+        bounds = bounds.union(&BoundingBox {
+            min: head,
+            max: head,
+        });
+    }
+
+    bounds
+}
+
+fn track_tail(motions: &[Motion], length: usize, visited_tiles: &mut impl VisitedSet) -> usize {
+    let mut knots = vec![(0, 0); length];
+    visited_tiles.insert(knots[length - 1]);
+
+    for Motion { direction, steps } in motions {
+        for _ in 0..*steps {
+            move_knots(&mut knots, direction);
+            visited_tiles.insert(knots[length - 1]);
+        }
+    }
+
+    visited_tiles.len()
+}
+
+fn count_visited_tiles(motions: &[Motion], length: usize) -> usize {
+    let bounds = get_head_bounding_box(motions);
+
+    if bounds.area() <= DENSE_BITMAP_LIMIT {
+        track_tail(motions, length, &mut DenseBitmap::new(bounds))
+    } else {
+        track_tail(motions, length, &mut ChunkedBitmap::default())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BoundingBox {
+    pub min: (i32, i32),
+    pub max: (i32, i32),
+}
+
+impl BoundingBox {
+    pub fn width(&self) -> u32 {
+        self.min.0.abs_diff(self.max.0) + 1
+    }
+
+    pub fn height(&self) -> u32 {
+        self.min.1.abs_diff(self.max.1) + 1
+    }
+
+    pub fn area(&self) -> u64 {
+        self.width() as u64 * self.height() as u64
+    }
+
+    pub fn union(&self, other: &BoundingBox) -> BoundingBox {
+        BoundingBox {
+            min: (self.min.0.min(other.min.0), self.min.1.min(other.min.1)),
+            max: (self.max.0.max(other.max.0), self.max.1.max(other.max.1)),
+        }
+    }
+
+    fn get_index(&self, (x, y): (i32, i32)) -> Option<usize> {
+        if x < self.min.0 || x > self.max.0 || y < self.min.1 || y > self.max.1 {
+            return None;
+        }
+
+        Some((y - self.min.1) as usize * self.width() as usize + (x - self.min.0) as usize)
+    }
+}
+
+const TRAIL_COLOR: (u8, u8, u8) = (160, 160, 160);
+const START_COLOR: (u8, u8, u8) = (0, 0, 255);
+const HEAD_COLOR: (u8, u8, u8) = (255, 0, 0);
+const KNOT_COLOR: (u8, u8, u8) = (0, 0, 0);
+
+pub struct Rope {
+    knots: Vec<(i32, i32)>,
+    paths: Vec<Vec<(i32, i32)>>,
+}
+
+impl Rope {
+    pub fn new(length: usize) -> Rope {
+        assert!(length > 0);
+
+        Rope {
+            knots: vec![(0, 0); length],
+            paths: vec![vec![(0, 0)]; length],
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.knots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.knots.is_empty()
+    }
+
+    pub fn knots(&self) -> &[(i32, i32)] {
+        &self.knots
+    }
+
+    pub fn step(&mut self, direction: &Direction) {
+        move_knots(&mut self.knots, direction);
+
+        for (path, knot) in self.paths.iter_mut().zip(&self.knots) {
+            path.push(*knot);
+        }
+    }
+
+    pub fn apply(&mut self, Motion { direction, steps }: &Motion) {
         for _ in 0..*steps {
-            let head = &mut knots[0];
+            self.step(direction);
+        }
+    }
+
+    pub fn path(&self, knot: usize) -> &[(i32, i32)] {
+        &self.paths[knot]
+    }
 
-            match direction {
-                Direction::Up => head.1 -= 1,
-                Direction::Down => head.1 += 1,
-                Direction::Left => head.0 -= 1,
-                Direction::Right => head.0 += 1,
+    pub fn visited(&self, knot: usize) -> HashSet<(i32, i32)> {
+        self.paths[knot].iter().copied().collect()
+    }
+
+    pub fn bounding_box(&self, knot: usize) -> BoundingBox {
+        self.paths[knot].iter().fold(
+            BoundingBox {
+                min: (i32::MAX, i32::MAX),
+                max: (i32::MIN, i32::MIN),
+            },
+            |bounding_box, &position| {
+                bounding_box.union(&BoundingBox {
+                    min: position,
+                    max: position,
+                })
+            },
+        )
+    }
+
+    pub fn total_bounding_box(&self) -> BoundingBox {
+        (1..self.len()).fold(self.bounding_box(0), |bounding_box, knot| {
+            bounding_box.union(&self.bounding_box(knot))
+        })
+    }
+
+    fn get_label(&self, knot: usize) -> char {
+        match knot {
+            0 => 'H',
+            knot if knot + 1 == self.len() && self.len() == 2 => 'T',
+            knot => char::from_digit(knot as u32 % 36, 36).unwrap(),
+        }
+    }
+
+    fn draw(&self, bounds: &BoundingBox, show_knots: bool, show_trail: bool) -> Vec<Option<usize>> {
+        let mut layers = vec![None; bounds.area() as usize];
+
+        if show_trail {
+            for position in self.path(self.len() - 1) {
+                if let Some(i) = bounds.get_index(*position) {
+                    layers[i] = Some(self.len() + 1);
+                }
             }
+        }
+
+        if let Some(i) = bounds.get_index((0, 0)) {
+            layers[i] = Some(self.len());
+        }
 
-            for i in 1..L {
-                knots[i] = get_new_knot_pos(knots[i], knots[i - 1]);
+        if show_knots {
+            for (knot, position) in self.knots.iter().enumerate().rev() {
+                if let Some(i) = bounds.get_index(*position) {
+                    layers[i] = Some(knot);
+                }
             }
+        }
+
+        layers
+    }
+
+    fn draw_text(&self, bounds: &BoundingBox, show_knots: bool, show_trail: bool) -> String {
+        let mut output = String::new();
 
-            visited_tiles.insert(knots[L - 1]);
+        for (i, layer) in self
+            .draw(bounds, show_knots, show_trail)
+            .into_iter()
+            .enumerate()
+        {
+            if i > 0 && i % bounds.width() as usize == 0 {
+                output.push('\n');
+            }
+
+            output.push(match layer {
+                None => '.',
+                Some(knot) if knot < self.len() => self.get_label(knot),
+                Some(knot) if knot == self.len() => 's',
+                Some(_) => '#',
+            });
         }
+
+        output
     }
 
-    visited_tiles.len()
+    pub fn render(&self, bounds: &BoundingBox) -> String {
+        self.draw_text(bounds, true, false)
+    }
+
+    pub fn render_trail(&self, bounds: &BoundingBox) -> String {
+        self.draw_text(bounds, false, true)
+    }
+
+    pub fn to_ppm(&self, bounds: &BoundingBox, scale: usize) -> String {
+        let width = bounds.width() as usize;
+        let layers = self.draw(bounds, true, true);
+        let mut output = format!(
+            "P3\n{} {}\n255\n",
+            width * scale,
+            bounds.height() as usize * scale
+        );
+
+        for row in layers.chunks(width) {
+            let pixels: Vec<String> = row
+                .iter()
+                .flat_map(|layer| {
+                    let (r, g, b) = match layer {
+                        None => (255, 255, 255),
+                        Some(0) => HEAD_COLOR,
+                        Some(knot) if *knot < self.len() => KNOT_COLOR,
+                        Some(knot) if *knot == self.len() => START_COLOR,
+                        Some(_) => TRAIL_COLOR,
+                    };
+                    vec![format!("{} {} {}", r, g, b); scale]
+                })
+                .collect();
+
+            for _ in 0..scale {
+                writeln!(output, "{}", pixels.join("  ")).unwrap();
+            }
+        }
+
+        output
+    }
+}
+
+fn collect_frames(
+    motions: &[Motion],
+    length: usize,
+    per_step: bool,
+    render: impl Fn(&Rope, &BoundingBox) -> String,
+) -> Vec<String> {
+    let mut rope = Rope::new(length);
+
+    for motion in motions {
+        rope.apply(motion);
+    }
+
+    let bounds = rope.total_bounding_box();
+    let mut rope = Rope::new(length);
+    let mut frames = vec![render(&rope, &bounds)];
+
+    for motion in motions {
+        if per_step {
+            for _ in 0..motion.steps {
+                rope.step(&motion.direction);
+                frames.push(render(&rope, &bounds));
+            }
+        } else {
+            rope.apply(motion);
+            frames.push(render(&rope, &bounds));
+        }
+    }
+
+    frames
+}
+
+pub fn render_frames(motions: &[Motion], length: usize, per_step: bool) -> Vec<String> {
+    collect_frames(motions, length, per_step, Rope::render)
+}
+
+pub fn render_image_frames(
+    motions: &[Motion],
+    length: usize,
+    per_step: bool,
+    scale: usize,
+) -> Vec<String> {
+    collect_frames(motions, length, per_step, |rope, bounds| {
+        rope.to_ppm(bounds, scale)
+    })
+}
+
+pub fn write_image_sequence(directory: &Path, frames: &[String]) -> io::Result<()> {
+    fs::create_dir_all(directory)?;
+
+    for (i, frame) in frames.iter().enumerate() {
+        fs::write(directory.join(format!("frame_{:05}.ppm", i)), frame)?;
+    }
+
+    Ok(())
 }
 
 #[aoc(day9, part1)]
 pub fn part1(input: &[Motion]) -> usize {
-    count_visited_tiles::<2>(input)
+    count_visited_tiles(input, 2)
 }
 
 #[aoc(day9, part2)]
 pub fn part2(input: &[Motion]) -> usize {
-    count_visited_tiles::<10>(input)
+    count_visited_tiles(input, 10)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[test]
-    fn sample1() {
-        let test_input = input_generator(
-            "R 4
+    const SAMPLE: &str = "R 4
 U 4
 L 3
 D 1
 R 4
 D 1
 L 5
-R 2",
-        );
+R 2";
+
+    #[test]
+    fn sample1() {
+        let test_input = input_generator(SAMPLE);
 
         assert_eq!(part1(&test_input), 13);
         assert_eq!(part2(&test_input), 1);
@@ -116,4 +567,108 @@ U 20",
 
         assert_eq!(part2(&test_input), 36);
     }
+
+    #[test]
+    fn rope_tracking() {
+        let mut rope = Rope::new(10);
+
+        for motion in input_generator("R 5\nU 8\nL 8\nD 3\nR 17\nD 10\nL 25\nU 20") {
+            rope.apply(&motion);
+        }
+
+        assert_eq!(rope.len(), 10);
+        assert_eq!(rope.visited(9).len(), 36);
+        assert_eq!(rope.path(0).len(), 97);
+        assert_eq!(rope.path(9).first(), Some(&(0, 0)));
+        assert_eq!(rope.knots()[0], (-11, -15));
+        assert_eq!(
+            rope.bounding_box(0),
+            BoundingBox {
+                min: (-11, -15),
+                max: (14, 5)
+            }
+        );
+        assert_eq!(rope.bounding_box(0).area(), 26 * 21);
+    }
+
+    #[test]
+    fn rendering() {
+        let test_input = input_generator(SAMPLE);
+        let frames = render_frames(&test_input, 2, false);
+
+        assert_eq!(frames.len(), 9);
+        assert_eq!(frames[0], "......\n......\n......\n......\nH.....");
+        assert_eq!(frames[1], "......\n......\n......\n......\ns..TH.");
+        assert_eq!(frames[8], "......\n......\n.TH...\n......\ns.....");
+        assert_eq!(render_frames(&test_input, 2, true).len(), 25);
+
+        let mut rope = Rope::new(2);
+        test_input.iter().for_each(|motion| rope.apply(motion));
+        assert_eq!(
+            rope.render_trail(&rope.total_bounding_box()),
+            "..##..\n...##.\n.####.\n....#.\ns###.."
+        );
+
+        let mut rope = Rope::new(10);
+        rope.apply(&test_input[0]);
+        assert_eq!(rope.render(&rope.total_bounding_box()), "4321H");
+
+        let image_frames = render_image_frames(&test_input, 2, false, 2);
+        assert_eq!(image_frames.len(), 9);
+        assert!(image_frames[0].starts_with("P3\n12 10\n255\n255 255 255"));
+    }
+
+    #[test]
+    fn diagonal_motions() {
+        let test_input = input_generator("UR 3\nDL 3\nUL 2\nDR 1");
+
+        assert_eq!(part1(&test_input), 5);
+        assert_eq!(
+            render_frames(&test_input, 2, false)[4],
+            "......\n.T....\n.H....\n..s..."
+        );
+    }
+
+    #[test]
+    fn motions_3d() {
+        assert_eq!(get_new_knot_pos_n([0, 0, 0], [1, 1, 2]), [1, 1, 1]);
+        assert_eq!(get_new_knot_pos_n([0, 0, 0], [1, -1, 1]), [0, 0, 0]);
+
+        let flat_input = input_generator(SAMPLE);
+        let flat_motions = parse_motions_3d(SAMPLE);
+        assert_eq!(count_visited_cells_3d(&flat_motions, 2), part1(&flat_input));
+        assert_eq!(
+            count_visited_cells_3d(&flat_motions, 10),
+            part2(&flat_input)
+        );
+
+        let test_input = parse_motions_3d("F 4\nURB 3\nD 2");
+        assert_eq!(count_visited_cells_3d(&test_input, 2), 6);
+    }
+
+    #[test]
+    fn visited_bitmaps() {
+        let test_input = input_generator(SAMPLE);
+        let bounds = get_head_bounding_box(&test_input);
+
+        assert_eq!(
+            bounds,
+            BoundingBox {
+                min: (0, -4),
+                max: (5, 0)
+            }
+        );
+        assert_eq!(
+            track_tail(&test_input, 2, &mut DenseBitmap::new(bounds)),
+            13
+        );
+        assert_eq!(
+            track_tail(&test_input, 2, &mut ChunkedBitmap::default()),
+            13
+        );
+
+        let huge_input = input_generator("R 30000\nU 30000\nL 60000\nD 1");
+        assert!(get_head_bounding_box(&huge_input).area() > DENSE_BITMAP_LIMIT);
+        assert_eq!(part1(&huge_input), 119998);
+    }
 }
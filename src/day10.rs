@@ -1,98 +1,836 @@
-use core::panic;
-use std::{fmt::Write, iter::Peekable, slice::Iter};
+use crate::ocr;
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    fmt::{self, Display, Write},
+    io::{self, BufRead},
+    iter,
+};
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Register {
+    X,
+    Y,
+    Z,
+    W,
+}
+
+impl Register {
+    fn parse(name: &str) -> Option<Register> {
+        match name {
+            "x" => Some(Register::X),
+            "y" => Some(Register::Y),
+            "z" => Some(Register::Z),
+            "w" => Some(Register::W),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Register {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Register::X => "x",
+            Register::Y => "y",
+            Register::Z => "z",
+            Register::W => "w",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operand {
+    Register(Register),
+    Value(i32),
+}
+
+impl Display for Operand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Operand::Register(register) => write!(f, "{}", register),
+            Operand::Value(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Condition {
+    Zero,
+    NotZero,
+    Positive,
+    Negative,
+}
+
+impl Condition {
+    fn holds(&self, value: i32) -> bool {
+        match self {
+            Condition::Zero => value == 0,
+            Condition::NotZero => value != 0,
+            Condition::Positive => value > 0,
+            Condition::Negative => value < 0,
+        }
+    }
+
+    fn mnemonic(&self) -> &'static str {
+        match self {
+            Condition::Zero => "jz",
+            Condition::NotZero => "jnz",
+            Condition::Positive => "jgz",
+            Condition::Negative => "jlz",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Instruction {
     Noop,
     AddX(i32),
+    Set(Register, Operand),
+    Add(Register, Operand),
+    Mul(Register, Operand),
+    Jump(usize),
+    JumpIf(Condition, Register, usize),
+}
+
+impl Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Instruction::Noop => write!(f, "noop"),
+            Instruction::AddX(value) => write!(f, "addx {}", value),
+            Instruction::Set(register, operand) => write!(f, "set {} {}", register, operand),
+            Instruction::Add(register, operand) => write!(f, "add {} {}", register, operand),
+            Instruction::Mul(register, operand) => write!(f, "mul {} {}", register, operand),
+            Instruction::Jump(target) => write!(f, "jmp label{}", target),
+            Instruction::JumpIf(condition, register, target) => {
+                write!(f, "{} {} label{}", condition.mnemonic(), register, target)
+            }
+        }
+    }
 }
 
-struct Processor<'a> {
-    instructions: Peekable<Iter<'a, Instruction>>,
-    x: i32,
+impl Instruction {
+    pub fn cycles(&self) -> u32 {
+        match self {
+            Instruction::Noop => 1,
+            Instruction::AddX(_) => 2,
+            Instruction::Set(..) => 1,
+            Instruction::Add(..) => 2,
+            Instruction::Mul(..) => 3,
+            Instruction::Jump(_) | Instruction::JumpIf(..) => 1,
+        }
+    }
+}
+
+pub struct Processor<'a> {
+    program: &'a [Instruction],
+    registers: [i32; 4],
+    pc: usize,
     cycle: u32,
-    next: u32,
+    remaining: u32,
 }
 
 impl Processor<'_> {
-    fn new(instructions: &[Instruction]) -> Processor {
+    pub fn new(program: &[Instruction]) -> Processor<'_> {
         Processor {
-            x: 1,
+            program,
+            registers: [1, 0, 0, 0],
+            pc: 0,
             cycle: 0,
-            next: 1,
-            instructions: instructions.iter().peekable(),
+            remaining: 0,
+        }
+    }
+
+    pub fn cycle(&self) -> u32 {
+        self.cycle
+    }
+
+    pub fn pc(&self) -> usize {
+        self.pc
+    }
+
+    pub fn register(&self, register: Register) -> i32 {
+        self.registers[register as usize]
+    }
+
+    pub fn x(&self) -> i32 {
+        self.register(Register::X)
+    }
+
+    pub fn current_instruction(&self) -> Option<&Instruction> {
+        self.program.get(self.pc)
+    }
+
+    pub fn is_halted(&self) -> bool {
+        self.pc >= self.program.len()
+    }
+
+    fn value(&self, operand: Operand) -> i32 {
+        match operand {
+            Operand::Register(register) => self.register(register),
+            Operand::Value(value) => value,
+        }
+    }
+
+    fn execute(&mut self, instruction: Instruction) {
+        self.pc += 1;
+
+        match instruction {
+            Instruction::Noop => (),
+            Instruction::AddX(value) => {
+                self.registers[Register::X as usize] = self.x().wrapping_add(value)
+            }
+            Instruction::Set(register, operand) => {
+                self.registers[register as usize] = self.value(operand)
+            }
+            Instruction::Add(register, operand) => {
+                self.registers[register as usize] =
+                    self.register(register).wrapping_add(self.value(operand))
+            }
+            Instruction::Mul(register, operand) => {
+                self.registers[register as usize] =
+                    self.register(register).wrapping_mul(self.value(operand))
+            }
+            Instruction::Jump(target) => self.pc = target,
+            Instruction::JumpIf(condition, register, target) => {
+                if condition.holds(self.register(register)) {
+                    self.pc = target;
+                }
+            }
         }
     }
 
-    fn step(&mut self) {
+    pub fn step(&mut self) -> bool {
+        let Some(&instruction) = self.current_instruction() else {
+            return false;
+        };
+
+        if self.remaining == 0 {
+            self.remaining = instruction.cycles();
+        }
+
         self.cycle += 1;
-        self.next -= 1;
+        self.remaining -= 1;
 
-        if self.next > 0 {
-            return;
+        if self.remaining == 0 {
+            self.execute(instruction);
         }
 
-        if let Some(Instruction::AddX(x)) = self.instructions.next() {
-            self.x += x;
+        true
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TraceEntry {
+    pub cycle: u32,
+    pub pc: usize,
+    pub instruction: Instruction,
+    pub x_during: i32,
+    pub x_after: i32,
+}
+
+impl Display for TraceEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "cycle {} pc {}: {} (x {} -> {})",
+            self.cycle, self.pc, self.instruction, self.x_during, self.x_after
+        )
+    }
+}
+
+impl<'a> Processor<'a> {
+    pub fn trace_step(&mut self) -> Option<TraceEntry> {
+        let pc = self.pc;
+        let instruction = *self.current_instruction()?;
+        let x_during = self.x();
+        self.step();
+
+        Some(TraceEntry {
+            cycle: self.cycle,
+            pc,
+            instruction,
+            x_during,
+            x_after: self.x(),
+        })
+    }
+
+    pub fn trace(mut self) -> impl Iterator<Item = TraceEntry> + 'a {
+        iter::from_fn(move || self.trace_step())
+    }
+}
+
+pub fn trace_to_csv(trace: &[TraceEntry]) -> String {
+    let mut output = String::from("cycle,pc,instruction,x_during,x_after\n");
+
+    for entry in trace {
+        writeln!(
+            output,
+            "{},{},{},{},{}",
+            entry.cycle, entry.pc, entry.instruction, entry.x_during, entry.x_after
+        )
+        .unwrap();
+    }
+
+    output
+}
+
+pub fn trace_to_json(trace: &[TraceEntry]) -> String {
+    let entries: Vec<String> = trace
+        .iter()
+        .map(|entry| {
+            format!(
+                r#"{{"cycle":{},"pc":{},"instruction":"{}","x_during":{},"x_after":{}}}"#,
+                entry.cycle, entry.pc, entry.instruction, entry.x_during, entry.x_after
+            )
+        })
+        .collect();
+
+    format!("[{}]", entries.join(","))
+}
+
+pub fn signal_strength(program: &[Instruction], cycles: impl IntoIterator<Item = u32>) -> i32 {
+    let cycles: BTreeSet<u32> = cycles.into_iter().collect();
+    let mut processor = Processor::new(program);
+
+    cycles
+        .into_iter()
+        .map(|cycle| {
+            while processor.cycle() + 1 < cycle && processor.step() {}
+            processor.x() * cycle as i32
+        })
+        .sum()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Breakpoint {
+    Cycle(u32),
+    Register(Register, i32),
+}
+
+impl Display for Breakpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Breakpoint::Cycle(cycle) => write!(f, "cycle {}", cycle),
+            Breakpoint::Register(register, value) => write!(f, "{} == {}", register, value),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Stop {
+    Breakpoint(usize),
+    Halted,
+}
+
+pub struct Debugger<'a> {
+    processor: Processor<'a>,
+    breakpoints: Vec<Breakpoint>,
+    previous: Option<[i32; 4]>,
+    stopped_at: Option<u32>,
+}
+
+impl<'a> Debugger<'a> {
+    pub fn new(program: &'a [Instruction]) -> Debugger<'a> {
+        Debugger {
+            processor: Processor::new(program),
+            breakpoints: Vec::new(),
+            previous: None,
+            stopped_at: None,
         }
+    }
+
+    pub fn processor(&self) -> &Processor<'a> {
+        &self.processor
+    }
+
+    pub fn add_breakpoint(&mut self, breakpoint: Breakpoint) -> usize {
+        self.breakpoints.push(breakpoint);
+        self.breakpoints.len() - 1
+    }
+
+    pub fn step(&mut self) -> Option<TraceEntry> {
+        let registers = self.processor.registers;
+        let entry = self.processor.trace_step()?;
+        self.previous = Some(registers);
+        Some(entry)
+    }
+
+    fn hit_breakpoint(&self) -> Option<usize> {
+        let cycle = self.processor.cycle() + 1;
+
+        self.breakpoints
+            .iter()
+            .position(|breakpoint| match *breakpoint {
+                Breakpoint::Cycle(other) => other == cycle,
+                Breakpoint::Register(register, value) => {
+                    self.processor.register(register) == value
+                        && self
+                            .previous
+                            .is_none_or(|registers| registers[register as usize] != value)
+                }
+            })
+    }
 
-        self.next = match self.instructions.peek() {
-            Some(Instruction::Noop) => 1,
-            Some(Instruction::AddX(_)) => 2,
-            None => 1,
+    pub fn run(&mut self) -> Stop {
+        loop {
+            if self.processor.is_halted() {
+                return Stop::Halted;
+            }
+
+            if self.stopped_at != Some(self.processor.cycle()) {
+                if let Some(i) = self.hit_breakpoint() {
+                    self.stopped_at = Some(self.processor.cycle());
+                    return Stop::Breakpoint(i);
+                }
+            }
+
+            self.step();
+        }
+    }
+
+    fn parse_breakpoint(args: &[&str]) -> Option<Breakpoint> {
+        match args {
+            ["cycle", cycle] => Some(Breakpoint::Cycle(cycle.parse().ok()?)),
+            [register, value] => Some(Breakpoint::Register(
+                Register::parse(register)?,
+                value.parse().ok()?,
+            )),
+            _ => None,
         }
     }
+
+    pub fn interact(&mut self, input: impl BufRead, mut output: impl io::Write) -> io::Result<()> {
+        for line in input.lines() {
+            let line = line?;
+            let words: Vec<&str> = line.split_whitespace().collect();
+
+            match words.as_slice() {
+                [] => (),
+                ["step" | "s", args @ ..] => {
+                    let count = match args {
+                        [] => 1,
+                        [count] => count.parse().unwrap_or(0),
+                        _ => 0,
+                    };
+
+                    for _ in 0..count {
+                        match self.step() {
+                            Some(entry) => writeln!(output, "{}", entry)?,
+                            None => {
+                                writeln!(output, "halted at cycle {}", self.processor.cycle())?;
+                                break;
+                            }
+                        }
+                    }
+                }
+                ["continue" | "c"] => match self.run() {
+                    Stop::Breakpoint(i) => writeln!(
+                        output,
+                        "breakpoint {} ({}) hit before cycle {}",
+                        i,
+                        self.breakpoints[i],
+                        self.processor.cycle() + 1
+                    )?,
+                    Stop::Halted => writeln!(output, "halted at cycle {}", self.processor.cycle())?,
+                },
+                ["break" | "b", args @ ..] => match Debugger::parse_breakpoint(args) {
+                    Some(breakpoint) => {
+                        let i = self.add_breakpoint(breakpoint);
+                        writeln!(output, "breakpoint {}: {}", i, breakpoint)?
+                    }
+                    None => writeln!(output, "invalid breakpoint: {}", args.join(" "))?,
+                },
+                ["print" | "p"] => {
+                    let processor = &self.processor;
+                    writeln!(
+                        output,
+                        "cycle {} pc {} x={} y={} z={} w={}",
+                        processor.cycle(),
+                        processor.pc(),
+                        processor.register(Register::X),
+                        processor.register(Register::Y),
+                        processor.register(Register::Z),
+                        processor.register(Register::W)
+                    )?
+                }
+                ["quit" | "q"] => break,
+                _ => writeln!(output, "unknown command: {}", line.trim())?,
+            }
+        }
+
+        Ok(())
+    }
 }
 
-#[aoc_generator(day10)]
-fn input_generator(input: &str) -> Vec<Instruction> {
-    input
-        .lines()
-        .map(|line| {
-            let mut parts = line.split(' ');
-            match parts.next().unwrap() {
-                "noop" => Instruction::Noop,
-                "addx" => Instruction::AddX(parts.next().unwrap().parse().unwrap()),
-                _ => panic!(),
+#[derive(Debug, PartialEq, Eq)]
+pub enum AssemblyError {
+    UnknownInstruction { line: usize, mnemonic: String },
+    MissingOperand { line: usize },
+    InvalidOperand { line: usize, operand: String },
+    UndefinedLabel { line: usize, label: String },
+    DuplicateLabel { line: usize, label: String },
+}
+
+impl Display for AssemblyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AssemblyError::UnknownInstruction { line, mnemonic } => {
+                write!(f, "line {}: unknown instruction {:?}", line, mnemonic)
             }
-        })
+            AssemblyError::MissingOperand { line } => {
+                write!(f, "line {}: missing operand", line)
+            }
+            AssemblyError::InvalidOperand { line, operand } => {
+                write!(f, "line {}: invalid operand {:?}", line, operand)
+            }
+            AssemblyError::UndefinedLabel { line, label } => {
+                write!(f, "line {}: undefined label {:?}", line, label)
+            }
+            AssemblyError::DuplicateLabel { line, label } => {
+                write!(f, "line {}: duplicate label {:?}", line, label)
+            }
+        }
+    }
+}
+
+struct Statement<'a> {
+    line: usize,
+    mnemonic: &'a str,
+    operands: Vec<&'a str>,
+}
+
+impl Statement<'_> {
+    fn operand(&self, i: usize) -> Result<&str, AssemblyError> {
+        self.operands
+            .get(i)
+            .copied()
+            .ok_or(AssemblyError::MissingOperand { line: self.line })
+    }
+
+    fn invalid_operand(&self, operand: &str) -> AssemblyError {
+        AssemblyError::InvalidOperand {
+            line: self.line,
+            operand: operand.to_string(),
+        }
+    }
+
+    fn value(&self, i: usize) -> Result<i32, AssemblyError> {
+        let operand = self.operand(i)?;
+        operand.parse().map_err(|_| self.invalid_operand(operand))
+    }
+
+    fn register(&self, i: usize) -> Result<Register, AssemblyError> {
+        let operand = self.operand(i)?;
+        Register::parse(operand).ok_or_else(|| self.invalid_operand(operand))
+    }
+
+    fn source(&self, i: usize) -> Result<Operand, AssemblyError> {
+        let operand = self.operand(i)?;
+
+        match operand.parse() {
+            Ok(value) => Ok(Operand::Value(value)),
+            Err(_) => Register::parse(operand)
+                .map(Operand::Register)
+                .ok_or_else(|| self.invalid_operand(operand)),
+        }
+    }
+
+    fn target(&self, i: usize, labels: &HashMap<&str, usize>) -> Result<usize, AssemblyError> {
+        let label = self.operand(i)?;
+
+        labels
+            .get(label)
+            .copied()
+            .ok_or_else(|| AssemblyError::UndefinedLabel {
+                line: self.line,
+                label: label.to_string(),
+            })
+    }
+
+    fn assemble(&self, labels: &HashMap<&str, usize>) -> Result<Instruction, AssemblyError> {
+        let (instruction, arity) = match self.mnemonic {
+            "noop" => (Instruction::Noop, 0),
+            "addx" => (Instruction::AddX(self.value(0)?), 1),
+            "set" => (Instruction::Set(self.register(0)?, self.source(1)?), 2),
+            "add" => (Instruction::Add(self.register(0)?, self.source(1)?), 2),
+            "mul" => (Instruction::Mul(self.register(0)?, self.source(1)?), 2),
+            "jmp" => (Instruction::Jump(self.target(0, labels)?), 1),
+            mnemonic => {
+                let condition = [
+                    Condition::Zero,
+                    Condition::NotZero,
+                    Condition::Positive,
+                    Condition::Negative,
+                ]
+                .into_iter()
+                .find(|condition| condition.mnemonic() == mnemonic)
+                .ok_or_else(|| AssemblyError::UnknownInstruction {
+                    line: self.line,
+                    mnemonic: mnemonic.to_string(),
+                })?;
+
+                (
+                    Instruction::JumpIf(condition, self.register(0)?, self.target(1, labels)?),
+                    2,
+                )
+            }
+        };
+
+        match self.operands.get(arity) {
+            Some(operand) => Err(self.invalid_operand(operand)),
+            None => Ok(instruction),
+        }
+    }
+}
+
+pub fn assemble(source: &str) -> Result<Vec<Instruction>, AssemblyError> {
+    let mut labels = HashMap::new();
+    let mut statements = Vec::new();
+
+    for (i, line) in source.lines().enumerate() {
+        let mut code = line.split(';').next().unwrap().trim();
+
+        while let Some((label, rest)) = code.split_once(':') {
+            let label = label.trim();
+
+            if labels.insert(label, statements.len()).is_some() {
+                return Err(AssemblyError::DuplicateLabel {
+                    line: i + 1,
+                    label: label.to_string(),
+                });
+            }
+
+            code = rest.trim();
+        }
+
+        let mut parts = code.split_whitespace();
+
+        if let Some(mnemonic) = parts.next() {
+            statements.push(Statement {
+                line: i + 1,
+                mnemonic,
+                operands: parts.collect(),
+            });
+        }
+    }
+
+    statements
+        .iter()
+        .map(|statement| statement.assemble(&labels))
         .collect()
 }
 
+pub fn disassemble(program: &[Instruction]) -> String {
+    let targets: HashSet<usize> = program
+        .iter()
+        .filter_map(|instruction| match instruction {
+            Instruction::Jump(target) | Instruction::JumpIf(_, _, target) => Some(*target),
+            _ => None,
+        })
+        .collect();
+    let mut output = String::new();
+
+    for (i, instruction) in program.iter().enumerate() {
+        if targets.contains(&i) {
+            writeln!(output, "label{}:", i).unwrap();
+        }
+
+        writeln!(output, "{}", instruction).unwrap();
+    }
+
+    if targets.contains(&program.len()) {
+        writeln!(output, "label{}:", program.len()).unwrap();
+    }
+
+    output
+}
+
+#[aoc_generator(day10)]
+fn input_generator(input: &str) -> Vec<Instruction> {
+    assemble(input).unwrap()
+}
+
 #[aoc(day10, part1)]
 pub fn part1(input: &[Instruction]) -> i32 {
-    let mut processor = Processor::new(input);
-    let mut sum = 0;
+    signal_strength(input, [20, 60, 100, 140, 180, 220])
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Crt {
+    pub width: usize,
+    pub height: Option<usize>,
+    pub sprite_width: usize,
+    pub max_rows: usize,
+}
 
-    for cycle in [20, 60, 100, 140, 180, 220] {
-        while processor.cycle < cycle - 2 {
-            processor.step();
+impl Default for Crt {
+    fn default() -> Self {
+        Crt {
+            width: 40,
+            height: Some(6),
+            sprite_width: 3,
+            max_rows: 1000,
         }
+    }
+}
 
-        sum += processor.x * cycle as i32
+fn is_lit(x: i32, column: i32, sprite_width: usize) -> bool {
+    if sprite_width == 0 {
+        return false;
     }
 
-    sum
+    let left = (sprite_width as i32 - 1) / 2;
+    let right = sprite_width as i32 / 2;
+
+    x - left <= column && column <= x + right
+}
+
+impl Crt {
+    pub fn render(&self, program: &[Instruction]) -> Option<String> {
+        if self.width == 0 {
+            return None;
+        }
+
+        let mut processor = Processor::new(program);
+        let mut output = String::new();
+        let mut row = 0;
+
+        while self
+            .height
+            .map_or(!processor.is_halted(), |height| row < height)
+        {
+            if self.height.is_none() && row == self.max_rows {
+                return None;
+            }
+
+            output.write_char('\n').unwrap();
+
+            for i in 0..self.width as i32 {
+                if self.height.is_none() && processor.is_halted() {
+                    break;
+                }
+
+                let char = if is_lit(processor.x(), i, self.sprite_width) {
+                    '#'
+                } else {
+                    '.'
+                };
+                output.write_char(char).unwrap();
+                processor.step();
+            }
+
+            row += 1;
+        }
+
+        Some(output)
+    }
 }
 
 #[aoc(day10, part2)]
 pub fn part2(input: &[Instruction]) -> String {
-    let mut processor = Processor::new(input);
-    let mut output = String::new();
-    let mut sprite_pos = processor.x;
+    Crt::default().render(input).unwrap()
+}
+
+#[aoc(day10, part2, ocr)]
+pub fn part2_ocr(input: &[Instruction]) -> String {
+    ocr::read(&part2(input)).unwrap().text
+}
+
+pub fn synthesize(image: &str, sprite_width: usize) -> Option<Vec<Instruction>> {
+    let rows: Vec<&str> = image.lines().filter(|line| !line.is_empty()).collect();
+    let width = rows.first()?.chars().count();
 
-    for _ in 0..6 {
-        output.write_char('\n').unwrap();
+    if rows.iter().any(|row| row.chars().count() != width)
+        || rows
+            .iter()
+            .flat_map(|row| row.chars())
+            .any(|ch| ch != '#' && ch != '.')
+    {
+        return None;
+    }
+
+    let pixels: Vec<bool> = rows
+        .iter()
+        .flat_map(|row| row.chars())
+        .map(|ch| ch == '#')
+        .collect();
+    let cycles = pixels.len();
+
+    let left = (sprite_width as i32 - 1) / 2;
+    let right = sprite_width as i32 / 2;
+    let values: Vec<i32> = (-right - 1..=width as i32 + left).collect();
+    let allowed =
+        |cycle: usize, x: i32| is_lit(x, (cycle % width) as i32, sprite_width) == pixels[cycle];
 
-        for i in 0..40 {
-            let distance = (sprite_pos - i).abs();
-            let char = if distance <= 1 { '#' } else { '.' };
-            output.write_char(char).unwrap();
-            sprite_pos = processor.x;
-            processor.step();
+    let mut halts = vec![vec![true; values.len()]; cycles + 1];
+    for cycle in (0..cycles).rev() {
+        for (j, &x) in values.iter().enumerate() {
+            halts[cycle][j] = halts[cycle + 1][j] && allowed(cycle, x);
         }
     }
 
-    output
+    let mut best: Vec<Vec<Option<usize>>> = vec![vec![None; values.len()]; cycles + 2];
+    let mut parents = vec![vec![(0, Instruction::Noop); values.len()]; cycles + 2];
+    best[0][values.iter().position(|&x| x == 1)?] = Some(0);
+    let mut end = None;
+
+    for cycle in 0..=cycles {
+        for (j, &x) in values.iter().enumerate() {
+            let Some(count) = best[cycle][j] else {
+                continue;
+            };
+
+            if halts[cycle][j] {
+                if end.is_none_or(|(_, _, best_count)| count < best_count) {
+                    end = Some((cycle, j, count));
+                }
+                continue;
+            }
+
+            if !allowed(cycle, x) {
+                continue;
+            }
+
+            let mut relax = |target: usize, k: usize, instruction: Instruction| {
+                if best[target][k].is_none_or(|other| count + 1 < other) {
+                    best[target][k] = Some(count + 1);
+                    parents[target][k] = (j, instruction);
+                }
+            };
+            relax(cycle + 1, j, Instruction::Noop);
+
+            if cycle + 1 < cycles && allowed(cycle + 1, x) {
+                for (k, &next) in values.iter().enumerate() {
+                    relax(cycle + 2, k, Instruction::AddX(next - x));
+                }
+            }
+        }
+    }
+
+    let (mut cycle, mut j, count) = end?;
+    let mut program = Vec::with_capacity(count);
+
+    while cycle > 0 {
+        let (previous, instruction) = parents[cycle][j];
+        cycle -= instruction.cycles() as usize;
+        j = previous;
+        program.push(instruction);
+    }
+
+    program.reverse();
+    let crt = Crt {
+        width,
+        height: Some(rows.len()),
+        sprite_width,
+        ..Crt::default()
+    };
+
+    if crt.render(&program)?.trim_start() != rows.join("\n") {
+        return None;
+    }
+
+    Some(program)
 }
 
 #[cfg(test)]
@@ -115,4 +853,257 @@ mod tests {
 #######.......#######.......#######....."
         );
     }
+
+    const PROGRAM: &str = "; double x three times, then add 5
+set y 3
+loop:
+    mul x 2     ; three cycles
+    add y -1
+    jnz y loop
+addx 5";
+
+    #[test]
+    fn extended_instructions() {
+        let program = assemble(PROGRAM).unwrap();
+        let mut processor = Processor::new(&program);
+
+        while processor.step() {}
+
+        assert_eq!(processor.x(), 13);
+        assert_eq!(processor.register(Register::Y), 0);
+        assert_eq!(processor.cycle(), 21);
+        assert!(processor.is_halted());
+
+        let program = assemble("set x 65536\nmul x x\nset y 2147483647\nadd y 1").unwrap();
+        let mut processor = Processor::new(&program);
+
+        while processor.step() {}
+
+        assert_eq!(processor.x(), 0);
+        assert_eq!(processor.register(Register::Y), i32::MIN);
+    }
+
+    #[test]
+    fn disassembly() {
+        let program = assemble(PROGRAM).unwrap();
+        let source = disassemble(&program);
+
+        assert_eq!(
+            source,
+            "set y 3\nlabel1:\nmul x 2\nadd y -1\njnz y label1\naddx 5\n"
+        );
+        assert_eq!(assemble(&source).unwrap(), program);
+
+        let sample = include_str!("../input/2022/day10.sample.txt");
+        assert_eq!(
+            disassemble(&assemble(sample).unwrap()).trim_end(),
+            sample.trim_end()
+        );
+    }
+
+    #[test]
+    fn assembly_errors() {
+        assert_eq!(
+            assemble("noop\nfoo 1"),
+            Err(AssemblyError::UnknownInstruction {
+                line: 2,
+                mnemonic: "foo".to_string()
+            })
+        );
+        assert_eq!(
+            assemble("addx"),
+            Err(AssemblyError::MissingOperand { line: 1 })
+        );
+        assert_eq!(
+            assemble("add q 1"),
+            Err(AssemblyError::InvalidOperand {
+                line: 1,
+                operand: "q".to_string()
+            })
+        );
+        assert_eq!(
+            assemble("jz x end"),
+            Err(AssemblyError::UndefinedLabel {
+                line: 1,
+                label: "end".to_string()
+            })
+        );
+        assert_eq!(
+            assemble("a: noop\na: noop").unwrap_err().to_string(),
+            "line 2: duplicate label \"a\""
+        );
+    }
+
+    #[test]
+    fn tracing() {
+        let test_input = input_generator("noop\naddx 3\naddx -5");
+        let trace: Vec<TraceEntry> = Processor::new(&test_input).trace().collect();
+
+        assert_eq!(trace.len(), 5);
+        assert_eq!(
+            trace[2],
+            TraceEntry {
+                cycle: 3,
+                pc: 1,
+                instruction: Instruction::AddX(3),
+                x_during: 1,
+                x_after: 4,
+            }
+        );
+        assert_eq!(
+            trace_to_csv(&trace[..2]),
+            "cycle,pc,instruction,x_during,x_after\n1,0,noop,1,1\n2,1,addx 3,1,1\n"
+        );
+        assert_eq!(
+            trace_to_json(&trace[4..]),
+            r#"[{"cycle":5,"pc":2,"instruction":"addx -5","x_during":4,"x_after":-1}]"#
+        );
+
+        let sample = input_generator(include_str!("../input/2022/day10.sample.txt"));
+        assert_eq!(signal_strength(&sample, [220, 20]), 420 + 3960);
+        assert_eq!(signal_strength(&test_input, [4, 1000]), 4 * 4 - 1000);
+    }
+
+    #[test]
+    fn debugger() {
+        let program = assemble(PROGRAM).unwrap();
+        let mut debugger = Debugger::new(&program);
+        debugger.add_breakpoint(Breakpoint::Register(Register::X, 8));
+
+        assert_eq!(debugger.run(), Stop::Breakpoint(0));
+        assert_eq!(debugger.processor().cycle(), 16);
+        assert_eq!(debugger.run(), Stop::Halted);
+        assert_eq!(debugger.processor().x(), 13);
+
+        let noops = input_generator("noop\nnoop\nnoop");
+        let mut debugger = Debugger::new(&noops);
+        debugger.add_breakpoint(Breakpoint::Cycle(4));
+        debugger.add_breakpoint(Breakpoint::Cycle(1));
+        debugger.add_breakpoint(Breakpoint::Register(Register::X, 1));
+
+        assert_eq!(debugger.run(), Stop::Breakpoint(1));
+        assert_eq!(debugger.processor().cycle(), 0);
+        assert_eq!(debugger.run(), Stop::Halted);
+        assert_eq!(debugger.processor().cycle(), 3);
+
+        let mut debugger = Debugger::new(&program);
+        let mut output = Vec::new();
+        debugger
+            .interact(
+                "break cycle 5\nbreak y 1\nbreak q\nc\np\nstep 2\ncontinue\nc\nfoo\nq\nstep"
+                    .as_bytes(),
+                &mut output,
+            )
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "breakpoint 0: cycle 5
+breakpoint 1: y == 1
+invalid breakpoint: q
+breakpoint 0 (cycle 5) hit before cycle 5
+cycle 4 pc 2 x=2 y=3 z=0 w=0
+cycle 5 pc 2: add y -1 (x 2 -> 2)
+cycle 6 pc 2: add y -1 (x 2 -> 2)
+breakpoint 1 (y == 1) hit before cycle 13
+halted at cycle 21
+unknown command: foo
+"
+        );
+    }
+
+    #[test]
+    fn letters() {
+        let test_input = input_generator(include_str!("../input/2022/day10.txt"));
+
+        assert_eq!(part2_ocr(&test_input), "EKRHEPUZ");
+    }
+
+    #[test]
+    fn synthesis() {
+        let test_input = input_generator(include_str!("../input/2022/day10.txt"));
+        let image = part2(&test_input);
+        let program = synthesize(&image, 3).unwrap();
+
+        assert!(program.len() < test_input.len());
+        assert_eq!(part2(&program), image);
+        assert_eq!(part2_ocr(&program), "EKRHEPUZ");
+
+        assert_eq!(
+            synthesize(&format!("###{}", ".".repeat(37)), 3),
+            Some(vec![])
+        );
+        assert_eq!(
+            synthesize("##...\n...##", 3),
+            Some(vec![
+                Instruction::AddX(-3),
+                Instruction::Noop,
+                Instruction::AddX(6)
+            ])
+        );
+        assert_eq!(synthesize(".###", 3), None);
+        assert_eq!(synthesize("##\n#", 3), None);
+        assert_eq!(synthesize("#x", 3), None);
+        assert_eq!(synthesize("", 1), None);
+    }
+
+    #[test]
+    fn crt_geometry() {
+        let test_input = input_generator(include_str!("../input/2022/day10.sample.txt"));
+        let until_end = Crt {
+            height: None,
+            ..Crt::default()
+        };
+
+        assert_eq!(until_end.render(&test_input).unwrap(), part2(&test_input));
+
+        let program = input_generator("addx 3\nnoop\naddx -4");
+        let crt = Crt {
+            width: 4,
+            height: None,
+            sprite_width: 1,
+            max_rows: 2,
+        };
+
+        assert_eq!(crt.render(&program).unwrap(), "\n.#..\n.");
+        assert_eq!(
+            Crt {
+                height: Some(3),
+                sprite_width: 4,
+                ..crt
+            }
+            .render(&program)
+            .unwrap(),
+            "\n##.#\n.##.\n###."
+        );
+        assert_eq!(
+            Crt {
+                sprite_width: 0,
+                ..crt
+            }
+            .render(&program)
+            .unwrap(),
+            "\n....\n."
+        );
+        assert_eq!(Crt { width: 0, ..crt }.render(&program), None);
+        assert_eq!(Crt { max_rows: 1, ..crt }.render(&program), None);
+        assert_eq!(crt.render(&assemble("l: jmp l").unwrap()), None);
+        assert_eq!(synthesize("....", 0), Some(vec![]));
+        assert_eq!(synthesize("#...", 0), None);
+
+        let image = ".##..\n..##.\n##...";
+        let program = synthesize(image, 2).unwrap();
+        assert_eq!(
+            Crt {
+                width: 5,
+                height: Some(3),
+                sprite_width: 2,
+                ..Crt::default()
+            }
+            .render(&program)
+            .unwrap()
+            .trim_start(),
+            image
+        );
+    }
 }
@@ -51,7 +51,7 @@ fn input_generator(input: &str) -> Vec<Monkey> {
         };
 
         fn parse_i64(line: &str) -> i64 {
-            line.split(' ').last().unwrap().parse().unwrap()
+            line.split(' ').next_back().unwrap().parse().unwrap()
         }
 
         monkeys.push(Monkey {
@@ -1,6 +1,7 @@
-use itertools::Itertools;
 use regex::Regex;
 
+use crate::interval_set::IntervalSet;
+
 #[derive(Debug)]
 struct Sensor {
     position: (i32, i32),
@@ -36,30 +37,8 @@ fn input_generator(input: &str) -> Vec<Sensor> {
         .collect()
 }
 
-fn normalize_ranges(ranges: &mut [(i32, i32)]) -> Vec<(i32, i32)> {
-    ranges.sort();
-
-    let mut ranges = ranges.iter().peekable();
-    let mut normalized_ranges = Vec::with_capacity(ranges.len());
-
-    while let Some(&(from, mut to)) = ranges.next() {
-        while let Some(next) = ranges.peek() {
-            if next.0 <= to + 1 {
-                to = to.max(next.1);
-                ranges.next();
-            } else {
-                break;
-            }
-        }
-
-        normalized_ranges.push((from, to));
-    }
-
-    normalized_ranges
-}
-
-fn get_beacon_ranges(sensors: &[Sensor], y: i32) -> Vec<(i32, i32)> {
-    let mut ranges = Vec::with_capacity(sensors.len());
+fn get_beacon_ranges(sensors: &[Sensor], y: i32) -> IntervalSet<i32> {
+    let mut ranges = IntervalSet::new();
 
     for sensor in sensors {
         let y_diff = sensor.position.1.abs_diff(y);
@@ -72,28 +51,21 @@ fn get_beacon_ranges(sensors: &[Sensor], y: i32) -> Vec<(i32, i32)> {
         let min_x = sensor.position.0 - side_len;
         let max_x = sensor.position.0 + side_len;
 
-        ranges.push((min_x, max_x));
+        ranges.insert(min_x..max_x + 1);
     }
 
-    normalize_ranges(&mut ranges)
-}
-
-fn count_range_elements(ranges: &[(i32, i32)]) -> i32 {
-    ranges.iter().map(|(from, to)| to - from + 1).sum()
+    ranges
 }
 
 fn count_positions_without_a_beacon(sensors: &[Sensor], y: i32) -> i32 {
-    let ranges = get_beacon_ranges(sensors, y);
-    let mut count = count_range_elements(&ranges);
+    let mut ranges = get_beacon_ranges(sensors, y);
 
     sensors
         .iter()
         .filter(|sensor| sensor.closest_beacon.1 == y)
-        .map(|sensor| sensor.closest_beacon.0)
-        .unique()
-        .for_each(|_| count -= 1);
+        .for_each(|sensor| ranges.remove(sensor.closest_beacon.0..sensor.closest_beacon.0 + 1));
 
-    count
+    ranges.len()
 }
 
 #[aoc(day15, part1)]
@@ -103,10 +75,10 @@ fn part1(input: &[Sensor]) -> i32 {
 
 fn get_tuning_frequency(sensors: &[Sensor], max: i32) -> Option<i64> {
     for y in 0..=max {
-        for (prev, curr) in get_beacon_ranges(sensors, y).iter().tuple_windows() {
-            let x = prev.1 + 1;
+        for gap in get_beacon_ranges(sensors, y).gaps() {
+            let x = gap.start;
 
-            if x == curr.0 - 1 && 0 <= x && x <= max {
+            if gap.end == x + 1 && 0 <= x && x <= max {
                 return Some(x as i64 * 4000000 + y as i64);
             }
         }
@@ -87,7 +87,7 @@ fn get_max_released_pressure<const L: usize>(
     mut agents: [Agent; L],
     minutes: usize,
 ) -> usize {
-    agents.sort_by(|a, b| a.sleep.cmp(&b.sleep));
+    agents.sort_by_key(|a| a.sleep);
 
     let Agent {
         position, sleep, ..
@@ -97,7 +97,7 @@ fn get_max_released_pressure<const L: usize>(
         return 0;
     }
 
-    for mut agent in agents.iter_mut() {
+    for agent in agents.iter_mut() {
         agent.sleep -= sleep;
     }
 
@@ -60,7 +60,7 @@ fn get_max_geodes(
                     return usize::MAX;
                 }
 
-                (cost - resource + robots - 1) / robots
+                (cost - resource).div_ceil(robots)
             })
             .max()
             .unwrap();
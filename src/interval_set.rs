@@ -0,0 +1,213 @@
+use std::ops::{Add, Range, Sub};
+
+pub trait Integer: Copy + Ord + Default + Add<Output = Self> + Sub<Output = Self> {}
+
+impl<T: Copy + Ord + Default + Add<Output = T> + Sub<Output = T>> Integer for T {}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct IntervalSet<T: Integer> {
+    ranges: Vec<Range<T>>,
+}
+
+impl<T: Integer> IntervalSet<T> {
+    pub fn new() -> IntervalSet<T> {
+        IntervalSet { ranges: Vec::new() }
+    }
+
+    pub fn insert(&mut self, range: Range<T>) {
+        if range.is_empty() {
+            return;
+        }
+
+        let first = self.ranges.partition_point(|other| other.end < range.start);
+        let last = self
+            .ranges
+            .partition_point(|other| other.start <= range.end);
+
+        let merged = if first < last {
+            self.ranges[first].start.min(range.start)..self.ranges[last - 1].end.max(range.end)
+        } else {
+            range
+        };
+
+        self.ranges.splice(first..last, [merged]);
+    }
+
+    pub fn remove(&mut self, range: Range<T>) {
+        if range.is_empty() {
+            return;
+        }
+
+        let first = self
+            .ranges
+            .partition_point(|other| other.end <= range.start);
+        let last = self.ranges.partition_point(|other| other.start < range.end);
+
+        if first >= last {
+            return;
+        }
+
+        let mut remainders = Vec::with_capacity(2);
+
+        if self.ranges[first].start < range.start {
+            remainders.push(self.ranges[first].start..range.start);
+        }
+
+        if range.end < self.ranges[last - 1].end {
+            remainders.push(range.end..self.ranges[last - 1].end);
+        }
+
+        self.ranges.splice(first..last, remainders);
+    }
+
+    pub fn union(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut union = self.clone();
+
+        for range in other.ranges() {
+            union.insert(range.clone());
+        }
+
+        union
+    }
+
+    pub fn intersection(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut ranges = Vec::new();
+        let mut a = self.ranges.iter().peekable();
+        let mut b = other.ranges.iter().peekable();
+
+        while let (Some(range_a), Some(range_b)) = (a.peek(), b.peek()) {
+            let start = range_a.start.max(range_b.start);
+            let end = range_a.end.min(range_b.end);
+
+            if start < end {
+                ranges.push(start..end);
+            }
+
+            if range_a.end < range_b.end {
+                a.next();
+            } else {
+                b.next();
+            }
+        }
+
+        IntervalSet { ranges }
+    }
+
+    pub fn difference(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut difference = self.clone();
+
+        for range in other.ranges() {
+            difference.remove(range.clone());
+        }
+
+        difference
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let i = self.ranges.partition_point(|range| range.end <= value);
+        self.ranges.get(i).is_some_and(|range| range.start <= value)
+    }
+
+    pub fn contains_range(&self, range: &Range<T>) -> bool {
+        if range.is_empty() {
+            return true;
+        }
+
+        let i = self.ranges.partition_point(|other| other.end < range.end);
+        self.ranges
+            .get(i)
+            .is_some_and(|other| other.start <= range.start)
+    }
+
+    pub fn is_superset(&self, other: &IntervalSet<T>) -> bool {
+        other.ranges().all(|range| self.contains_range(range))
+    }
+
+    pub fn overlaps(&self, other: &IntervalSet<T>) -> bool {
+        !self.intersection(other).is_empty()
+    }
+
+    pub fn len(&self) -> T {
+        self.ranges
+            .iter()
+            .fold(T::default(), |len, range| len + (range.end - range.start))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    pub fn span(&self) -> Option<Range<T>> {
+        Some(self.ranges.first()?.start..self.ranges.last()?.end)
+    }
+
+    pub fn ranges(&self) -> impl Iterator<Item = &Range<T>> {
+        self.ranges.iter()
+    }
+
+    pub fn gaps(&self) -> impl Iterator<Item = Range<T>> + '_ {
+        self.ranges
+            .windows(2)
+            .map(|pair| pair[0].end..pair[1].start)
+    }
+}
+
+impl<T: Integer> From<Range<T>> for IntervalSet<T> {
+    fn from(range: Range<T>) -> Self {
+        let mut set = IntervalSet::new();
+        set.insert(range);
+        set
+    }
+}
+
+impl<T: Integer> FromIterator<Range<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Range<T>>>(iter: I) -> Self {
+        let mut set = IntervalSet::new();
+
+        for range in iter {
+            set.insert(range);
+        }
+
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn insert_and_remove() {
+        let mut set: IntervalSet<i32> = [5..8, 0..2, 10..12].into_iter().collect();
+        assert_eq!(
+            set.ranges().cloned().collect::<Vec<_>>(),
+            [0..2, 5..8, 10..12]
+        );
+
+        set.insert(2..5);
+        set.insert(8..9);
+        assert_eq!(set.ranges().cloned().collect::<Vec<_>>(), [0..9, 10..12]);
+        assert_eq!(set.len(), 11);
+
+        set.remove(3..11);
+        assert_eq!(set.ranges().cloned().collect::<Vec<_>>(), [0..3, 11..12]);
+        assert_eq!(set.gaps().collect::<Vec<_>>(), vec![3..11]);
+        assert_eq!(set.span(), Some(0..12));
+        assert!(set.contains(2));
+        assert!(!set.contains(3));
+    }
+
+    #[test]
+    fn set_operations() {
+        let a: IntervalSet<u64> = [0..10, 20..30].into_iter().collect();
+        let b = IntervalSet::from(5..25);
+
+        assert_eq!(a.union(&b), IntervalSet::from(0..30));
+        assert_eq!(a.intersection(&b), [5..10, 20..25].into_iter().collect());
+        assert_eq!(a.difference(&b), [0..5, 25..30].into_iter().collect());
+        assert!(a.is_superset(&IntervalSet::from(21..29)));
+        assert!(!a.is_superset(&b));
+        assert!(a.overlaps(&b));
+        assert!(!a.overlaps(&IntervalSet::from(10..20)));
+    }
+}
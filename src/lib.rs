@@ -24,5 +24,7 @@ pub mod day18;
 pub mod day19;
 pub mod day20;
 pub mod day21;
+pub mod interval_set;
+pub mod ocr;
 
 aoc_lib! { year = 2022 }
@@ -0,0 +1,353 @@
+const SMALL_GLYPHS: [(char, [&str; 6]); 18] = [
+    ('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', [".###", "..#.", "..#.", "..#.", "..#.", ".###"]),
+    ('J', ["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', ["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', [".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Y', ["#...#", "#...#", ".#.#.", "..#..", "..#..", "..#.."]),
+    ('Z', ["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+const LARGE_GLYPHS: [(char, [&str; 10]); 15] = [
+    (
+        'A',
+        [
+            "..##..", ".#..#.", "#....#", "#....#", "#....#", "######", "#....#", "#....#",
+            "#....#", "#....#",
+        ],
+    ),
+    (
+        'B',
+        [
+            "#####.", "#....#", "#....#", "#....#", "#####.", "#....#", "#....#", "#....#",
+            "#....#", "#####.",
+        ],
+    ),
+    (
+        'C',
+        [
+            ".####.", "#....#", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....",
+            "#....#", ".####.",
+        ],
+    ),
+    (
+        'E',
+        [
+            "######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....",
+            "#.....", "######",
+        ],
+    ),
+    (
+        'F',
+        [
+            "######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....",
+            "#.....", "#.....",
+        ],
+    ),
+    (
+        'G',
+        [
+            ".####.", "#....#", "#.....", "#.....", "#.....", "#..###", "#....#", "#....#",
+            "#...##", ".###.#",
+        ],
+    ),
+    (
+        'H',
+        [
+            "#....#", "#....#", "#....#", "#....#", "######", "#....#", "#....#", "#....#",
+            "#....#", "#....#",
+        ],
+    ),
+    (
+        'J',
+        [
+            "...###", "....#.", "....#.", "....#.", "....#.", "....#.", "....#.", "#...#.",
+            "#...#.", ".###..",
+        ],
+    ),
+    (
+        'K',
+        [
+            "#....#", "#...#.", "#..#..", "#.#...", "##....", "##....", "#.#...", "#..#..",
+            "#...#.", "#....#",
+        ],
+    ),
+    (
+        'L',
+        [
+            "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....",
+            "#.....", "######",
+        ],
+    ),
+    (
+        'N',
+        [
+            "#....#", "##...#", "##...#", "#.#..#", "#.#..#", "#..#.#", "#..#.#", "#...##",
+            "#...##", "#....#",
+        ],
+    ),
+    (
+        'P',
+        [
+            "#####.", "#....#", "#....#", "#....#", "#####.", "#.....", "#.....", "#.....",
+            "#.....", "#.....",
+        ],
+    ),
+    (
+        'R',
+        [
+            "#####.", "#....#", "#....#", "#....#", "#####.", "#..#..", "#...#.", "#...#.",
+            "#....#", "#....#",
+        ],
+    ),
+    (
+        'X',
+        [
+            "#....#", "#....#", ".#..#.", ".#..#.", "..##..", "..##..", ".#..#.", ".#..#.",
+            "#....#", "#....#",
+        ],
+    ),
+    (
+        'Z',
+        [
+            "######", ".....#", ".....#", "....#.", "...#..", "..#...", ".#....", "#.....",
+            "#.....", "######",
+        ],
+    ),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Font {
+    Small,
+    Large,
+}
+
+impl Font {
+    pub fn height(&self) -> usize {
+        match self {
+            Font::Small => 6,
+            Font::Large => 10,
+        }
+    }
+
+    pub fn from_height(height: usize) -> Option<Font> {
+        [Font::Small, Font::Large]
+            .into_iter()
+            .find(|font| font.height() == height)
+    }
+
+    fn glyphs(&self) -> Vec<(char, Vec<&'static str>)> {
+        match self {
+            Font::Small => SMALL_GLYPHS
+                .iter()
+                .map(|(letter, rows)| (*letter, rows.to_vec()))
+                .collect(),
+            Font::Large => LARGE_GLYPHS
+                .iter()
+                .map(|(letter, rows)| (*letter, rows.to_vec()))
+                .collect(),
+        }
+    }
+
+    pub fn glyph(&self, letter: char) -> Option<Vec<&'static str>> {
+        self.glyphs()
+            .into_iter()
+            .find(|(other, _)| *other == letter)
+            .map(|(_, rows)| rows)
+    }
+
+    fn recognize_glyph(&self, glyph: &[Vec<bool>]) -> Option<char> {
+        self.glyphs()
+            .into_iter()
+            .find(|(_, rows)| trim_columns(&parse_pixels(rows)) == glyph)
+            .map(|(letter, _)| letter)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct UnknownGlyph {
+    pub column: usize,
+    pub pattern: String,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Recognition {
+    pub text: String,
+    pub unknown: Vec<UnknownGlyph>,
+}
+
+fn parse_pixels(rows: &[&str]) -> Vec<Vec<bool>> {
+    rows.iter()
+        .map(|row| row.chars().map(|ch| ch == '#').collect())
+        .collect()
+}
+
+fn format_pixels(pixels: &[Vec<bool>]) -> String {
+    pixels
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|&lit| if lit { '#' } else { '.' })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn is_blank_column(pixels: &[Vec<bool>], column: usize) -> bool {
+    pixels
+        .iter()
+        .all(|row| !row.get(column).copied().unwrap_or(false))
+}
+
+fn trim_columns(pixels: &[Vec<bool>]) -> Vec<Vec<bool>> {
+    let width = pixels.iter().map(Vec::len).max().unwrap_or(0);
+    let Some(start) = (0..width).find(|&x| !is_blank_column(pixels, x)) else {
+        return vec![vec![]; pixels.len()];
+    };
+    let end = (0..width).rfind(|&x| !is_blank_column(pixels, x)).unwrap() + 1;
+
+    pixels
+        .iter()
+        .map(|row| (start..end).map(|x| row.get(x) == Some(&true)).collect())
+        .collect()
+}
+
+fn get_glyph_columns(pixels: &[Vec<bool>]) -> Vec<(usize, usize)> {
+    let width = pixels.iter().map(Vec::len).max().unwrap_or(0);
+    let mut columns = Vec::new();
+    let mut start = None;
+
+    for x in 0..=width {
+        match (x < width && !is_blank_column(pixels, x), start) {
+            (true, None) => start = Some(x),
+            (false, Some(from)) => {
+                columns.push((from, x));
+                start = None;
+            }
+            _ => (),
+        }
+    }
+
+    columns
+}
+
+pub fn recognize(image: &str, font: Font) -> Option<Recognition> {
+    let rows: Vec<&str> = image.lines().filter(|line| !line.is_empty()).collect();
+
+    if rows.len() != font.height() {
+        return None;
+    }
+
+    let pixels = parse_pixels(&rows);
+    let mut text = String::new();
+    let mut unknown = Vec::new();
+
+    for (from, to) in get_glyph_columns(&pixels) {
+        let glyph: Vec<Vec<bool>> = pixels
+            .iter()
+            .map(|row| (from..to).map(|x| row.get(x) == Some(&true)).collect())
+            .collect();
+
+        match font.recognize_glyph(&glyph) {
+            Some(letter) => text.push(letter),
+            None => {
+                text.push('?');
+                unknown.push(UnknownGlyph {
+                    column: from,
+                    pattern: format_pixels(&glyph),
+                });
+            }
+        }
+    }
+
+    Some(Recognition { text, unknown })
+}
+
+pub fn read(image: &str) -> Option<Recognition> {
+    let height = image.lines().filter(|line| !line.is_empty()).count();
+    recognize(image, Font::from_height(height)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(text: &str, font: Font, spacing: usize) -> String {
+        (0..font.height())
+            .map(|y| {
+                text.chars()
+                    .map(|letter| font.glyph(letter).unwrap()[y].to_string())
+                    .collect::<Vec<_>>()
+                    .join(&".".repeat(spacing))
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn small_font() {
+        let letters: String = SMALL_GLYPHS.iter().map(|(letter, _)| letter).collect();
+
+        assert_eq!(
+            read(&render(&letters, Font::Small, 1)),
+            Some(Recognition {
+                text: letters,
+                unknown: vec![],
+            })
+        );
+        assert_eq!(
+            read(
+                "
+####.#..#.###..#..#.####.###..#..#.####.
+#....#.#..#..#.#..#.#....#..#.#..#....#.
+###..##...#..#.####.###..#..#.#..#...#..
+#....#.#..###..#..#.#....###..#..#..#...
+#....#.#..#.#..#..#.#....#....#..#.#....
+####.#..#.#..#.#..#.####.#.....##..####."
+            )
+            .unwrap()
+            .text,
+            "EKRHEPUZ"
+        );
+    }
+
+    #[test]
+    fn large_font() {
+        let letters: String = LARGE_GLYPHS.iter().map(|(letter, _)| letter).collect();
+
+        assert_eq!(
+            recognize(&render(&letters, Font::Large, 2), Font::Large)
+                .unwrap()
+                .text,
+            letters
+        );
+        assert_eq!(read("#\n#"), None);
+        assert_eq!(recognize(&render("AB", Font::Small, 1), Font::Large), None);
+    }
+
+    #[test]
+    fn unknown_glyphs() {
+        let recognition = read("#..#..##.\n#..#.#..#\n####.#..#\n#..#.#..#\n#..#..##.\n.....#.##");
+
+        assert_eq!(recognition.as_ref().unwrap().text, "??");
+        assert_eq!(
+            recognition.unwrap().unknown[1],
+            UnknownGlyph {
+                column: 5,
+                pattern: ".##.\n#..#\n#..#\n#..#\n.##.\n#.##".to_string()
+            }
+        );
+    }
+}